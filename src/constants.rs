@@ -3,15 +3,31 @@ pub const CDN_CACHE_MAX_AGE: u32 = 28_800;
 pub const STALE_WHILE_REVALIDATE: u32 = 86_400;
 
 pub const DEFAULT_PANEL_SIZE: i32 = 110;
+pub const MIN_PANEL_SIZE: i32 = 55;
+pub const MAX_PANEL_SIZE: i32 = 330;
 pub const DEFAULT_MAX_COLUMN: i32 = 8;
 pub const DEFAULT_MAX_ROW: i32 = 3;
 pub const DEFAULT_MARGIN_W: i32 = 0;
 pub const DEFAULT_MARGIN_H: i32 = 0;
 pub const DEFAULT_NO_BACKGROUND: bool = false;
 pub const DEFAULT_NO_FRAME: bool = false;
+pub const DEFAULT_NO_ANIMATION: bool = false;
+pub const DEFAULT_SCALE: f64 = 1.0;
+pub const EASY_SCALE: f64 = 0.5;
+pub const HARD_SCALE: f64 = 2.0;
+pub const MIN_SCALE: f64 = 0.1;
+pub const MAX_SCALE: f64 = 10.0;
+
+pub const MAX_TEAM_MEMBERS: usize = 10;
+
+pub const DEFAULT_SCORE_UNIT: &str = "pt";
+pub const MAX_SCORE_UNIT_LENGTH: usize = 8;
 
 pub const DEFAULT_GITHUB_API: &str = "https://api.github.com/graphql";
 pub const DEFAULT_GITHUB_RETRY_DELAY_MS: u64 = 500;
+// Organizations are paged 100 repositories at a time, up to this many pages.
+pub const MAX_ORGANIZATION_REPOSITORY_PAGES: usize = 10;
 
 pub const SVG_CACHE_TTL_SECS: u64 = 60 * 60;
 pub const USER_CACHE_TTL_SECS: u64 = 60 * 60 * 4;
+pub const USERNAME_LIMIT_WINDOW_SECS: u64 = 60 * 60;
@@ -0,0 +1,317 @@
+use std::{fs, sync::OnceLock};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    models::{MetricSource, OrganizationInfo, RepositoryInfo, UserInfo},
+    trophy::{Rank, RankCondition},
+};
+
+const BUILTIN_DEFINITIONS: &str = include_str!("trophies.json");
+const ALL_SUPER_RANK_METRIC: &str = "all_super_rank";
+
+static DEFINITIONS: OnceLock<TrophyDefinitions> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum DefinitionError {
+    #[error("failed to read trophy definitions from '{path}': {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("failed to parse trophy definitions: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("trophy '{title}' uses unknown metric '{metric}'")]
+    UnknownMetric { title: String, metric: String },
+    #[error("trophy '{title}' uses unknown rank '{rank}'")]
+    UnknownRank { title: String, rank: String },
+    #[error("trophy '{title}' has no ranks")]
+    MissingRanks { title: String },
+    #[error("trophy definitions are already initialized")]
+    AlreadyInitialized,
+}
+
+#[derive(Debug)]
+pub enum Metric {
+    Field(&'static str),
+    AllSuperRank(&'static [&'static str]),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreFormat {
+    #[default]
+    Points,
+    Months,
+}
+
+#[derive(Debug)]
+pub struct TrophyDefinition {
+    pub title: &'static str,
+    pub filter_titles: &'static [&'static str],
+    pub hidden: bool,
+    pub opt_in: bool,
+    pub metric: Metric,
+    pub icon: Option<&'static str>,
+    pub score_format: ScoreFormat,
+    pub rank_conditions: &'static [RankCondition],
+    pub bottom_override: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub struct TrophyDefinitions {
+    pub user: Vec<TrophyDefinition>,
+    pub organization: Vec<TrophyDefinition>,
+    pub repository: Vec<TrophyDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DefinitionFile {
+    #[serde(default)]
+    user: Vec<RawDefinition>,
+    #[serde(default)]
+    organization: Vec<RawDefinition>,
+    #[serde(default)]
+    repository: Vec<RawDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDefinition {
+    title: String,
+    #[serde(default)]
+    filter_titles: Vec<String>,
+    #[serde(default)]
+    hidden: bool,
+    // Only drawn when `title=` names the trophy.
+    #[serde(default)]
+    opt_in: bool,
+    metric: String,
+    icon: Option<String>,
+    #[serde(default)]
+    requires: Vec<String>,
+    #[serde(default)]
+    score_format: ScoreFormat,
+    bottom_override: Option<String>,
+    ranks: Vec<RawRankCondition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRankCondition {
+    rank: String,
+    message: String,
+    required_score: i64,
+}
+
+pub fn definitions() -> &'static TrophyDefinitions {
+    DEFINITIONS.get_or_init(|| load(None).expect("built-in trophy definitions must be valid"))
+}
+
+pub fn init_from_file(path: &str) -> Result<&'static TrophyDefinitions, DefinitionError> {
+    let overrides = fs::read_to_string(path).map_err(|source| DefinitionError::Io {
+        path: path.to_string(),
+        source,
+    })?;
+    let loaded = load(Some(&overrides))?;
+
+    DEFINITIONS
+        .set(loaded)
+        .map_err(|_| DefinitionError::AlreadyInitialized)?;
+    Ok(definitions())
+}
+
+fn load(overrides: Option<&str>) -> Result<TrophyDefinitions, DefinitionError> {
+    let mut file: DefinitionFile = serde_json::from_str(BUILTIN_DEFINITIONS)?;
+
+    if let Some(overrides) = overrides {
+        let overrides: DefinitionFile = serde_json::from_str(overrides)?;
+        merge(&mut file.user, overrides.user);
+        merge(&mut file.organization, overrides.organization);
+        merge(&mut file.repository, overrides.repository);
+    }
+
+    Ok(TrophyDefinitions {
+        user: build::<UserInfo>(file.user)?,
+        organization: build::<OrganizationInfo>(file.organization)?,
+        repository: build::<RepositoryInfo>(file.repository)?,
+    })
+}
+
+fn merge(base: &mut Vec<RawDefinition>, overrides: Vec<RawDefinition>) {
+    for definition in overrides {
+        match base
+            .iter_mut()
+            .find(|existing| existing.title.eq_ignore_ascii_case(&definition.title))
+        {
+            // The built-in title and aliases stay, so `title=` filters and `requires` lookups
+            // keep matching however the override spells them.
+            Some(existing) => {
+                let title = std::mem::take(&mut existing.title);
+                let mut filter_titles = std::mem::take(&mut existing.filter_titles);
+                if filter_titles.is_empty() {
+                    filter_titles.push(title.clone());
+                }
+                for alias in definition.filter_titles.iter().chain([&definition.title]) {
+                    if !filter_titles.contains(alias) {
+                        filter_titles.push(alias.clone());
+                    }
+                }
+                *existing = RawDefinition {
+                    title,
+                    filter_titles,
+                    ..definition
+                };
+            }
+            None => base.push(definition),
+        }
+    }
+}
+
+fn build<T: MetricSource>(
+    raw_definitions: Vec<RawDefinition>,
+) -> Result<Vec<TrophyDefinition>, DefinitionError> {
+    raw_definitions
+        .into_iter()
+        .map(|raw| {
+            let metric = if raw.metric == ALL_SUPER_RANK_METRIC {
+                Metric::AllSuperRank(leak_strings(raw.requires))
+            } else if let Some(name) = T::METRICS.iter().find(|name| **name == raw.metric) {
+                Metric::Field(name)
+            } else {
+                return Err(DefinitionError::UnknownMetric {
+                    title: raw.title,
+                    metric: raw.metric,
+                });
+            };
+
+            if raw.ranks.is_empty() {
+                return Err(DefinitionError::MissingRanks { title: raw.title });
+            }
+
+            let mut rank_conditions = Vec::with_capacity(raw.ranks.len());
+            for condition in raw.ranks {
+                let Some(rank) = Rank::from_name(&condition.rank) else {
+                    return Err(DefinitionError::UnknownRank {
+                        title: raw.title,
+                        rank: condition.rank,
+                    });
+                };
+                rank_conditions.push(RankCondition {
+                    rank,
+                    message: leak_str(condition.message),
+                    required_score: condition.required_score,
+                });
+            }
+
+            let filter_titles = if raw.filter_titles.is_empty() {
+                vec![raw.title.clone()]
+            } else {
+                raw.filter_titles
+            };
+
+            Ok(TrophyDefinition {
+                title: leak_str(raw.title),
+                filter_titles: leak_strings(filter_titles),
+                hidden: raw.hidden,
+                opt_in: raw.opt_in,
+                metric,
+                icon: raw.icon.map(leak_str),
+                score_format: raw.score_format,
+                rank_conditions: Box::leak(rank_conditions.into_boxed_slice()),
+                bottom_override: raw.bottom_override.map(leak_str),
+            })
+        })
+        .collect()
+}
+
+// Definitions are loaded once per process and referenced by every rendered trophy.
+fn leak_str(value: String) -> &'static str {
+    Box::leak(value.into_boxed_str())
+}
+
+fn leak_strings(values: Vec<String>) -> &'static [&'static str] {
+    Box::leak(
+        values
+            .into_iter()
+            .map(leak_str)
+            .collect::<Vec<_>>()
+            .into_boxed_slice(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DefinitionError, Metric, load};
+
+    #[test]
+    fn builtin_definitions_load() {
+        let definitions = load(None).expect("built-in definitions");
+        assert_eq!(definitions.user[0].title, "Stars");
+        assert_eq!(definitions.user[0].icon, Some("star"));
+        assert!(
+            definitions
+                .user
+                .iter()
+                .any(|definition| matches!(definition.metric, Metric::AllSuperRank(_)))
+        );
+        assert!(!definitions.organization.is_empty());
+        assert!(!definitions.repository.is_empty());
+    }
+
+    #[test]
+    fn overrides_replace_and_extend_builtin_trophies() {
+        let overrides = r#"{
+            "user": [
+                {
+                    "title": "stars",
+                    "metric": "total_stargazers",
+                    "ranks": [{ "rank": "S", "message": "Shining", "required_score": 5 }]
+                },
+                {
+                    "title": "Polyglot",
+                    "hidden": true,
+                    "metric": "language_count",
+                    "ranks": [{ "rank": "SECRET", "message": "Polyglot", "required_score": 20 }]
+                }
+            ]
+        }"#;
+
+        let definitions = load(Some(overrides)).expect("overrides");
+        let stars = &definitions.user[0];
+        assert_eq!(stars.title, "Stars");
+        assert_eq!(stars.filter_titles, ["Star", "Stars", "stars"]);
+        assert_eq!(stars.rank_conditions[0].message, "Shining");
+        for definition in &definitions.user {
+            if let Metric::AllSuperRank(requires) = definition.metric {
+                assert!(requires.iter().all(|required| {
+                    definitions
+                        .user
+                        .iter()
+                        .any(|other| other.title == *required)
+                }));
+            }
+        }
+
+        let polyglot = definitions.user.last().expect("appended trophy");
+        assert_eq!(polyglot.title, "Polyglot");
+        assert_eq!(polyglot.filter_titles, ["Polyglot"]);
+    }
+
+    #[test]
+    fn unknown_metric_is_rejected() {
+        let overrides = r#"{
+            "repository": [
+                {
+                    "title": "Followers",
+                    "metric": "total_followers",
+                    "ranks": [{ "rank": "C", "message": "First", "required_score": 1 }]
+                }
+            ]
+        }"#;
+
+        assert!(matches!(
+            load(Some(overrides)),
+            Err(DefinitionError::UnknownMetric { .. })
+        ));
+    }
+}
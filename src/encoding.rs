@@ -0,0 +1,124 @@
+use std::io::Write;
+
+use flate2::{Compression, write::GzEncoder};
+
+const BROTLI_QUALITY: u32 = 11;
+const BROTLI_WINDOW: u32 = 22;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Identity,
+    Gzip,
+    Brotli,
+}
+
+impl Encoding {
+    // Picks the highest-quality coding we can serve, preferring brotli on ties.
+    pub fn negotiate(accept_encoding: Option<&str>) -> Self {
+        let Some(accept_encoding) = accept_encoding else {
+            return Self::Identity;
+        };
+
+        let mut wildcard = None;
+        let mut qualities = [(Self::Brotli, None), (Self::Gzip, None)];
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';');
+            let coding = parts.next().unwrap_or_default().trim();
+            let quality = parts
+                .filter_map(|part| part.trim().strip_prefix("q="))
+                .find_map(|value| value.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            match coding.to_ascii_lowercase().as_str() {
+                "br" => qualities[0].1 = Some(quality),
+                "gzip" | "x-gzip" => qualities[1].1 = Some(quality),
+                "*" => wildcard = Some(quality),
+                _ => {}
+            }
+        }
+
+        qualities
+            .into_iter()
+            .filter_map(|(encoding, quality)| Some((encoding, quality.or(wildcard)?)))
+            .filter(|(_, quality)| *quality > 0.0)
+            .fold(None, |best: Option<(Self, f32)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            })
+            .map_or(Self::Identity, |(encoding, _)| encoding)
+    }
+
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Self::Identity => None,
+            Self::Gzip => Some("gzip"),
+            Self::Brotli => Some("br"),
+        }
+    }
+
+    pub fn compress(self, body: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            Self::Identity => None,
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+                encoder.write_all(body).ok()?;
+                encoder.finish().ok()
+            }
+            Self::Brotli => {
+                let mut output = Vec::new();
+                {
+                    let mut writer = brotli::CompressorWriter::new(
+                        &mut output,
+                        4_096,
+                        BROTLI_QUALITY,
+                        BROTLI_WINDOW,
+                    );
+                    writer.write_all(body).ok()?;
+                }
+                Some(output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::Encoding;
+
+    #[test]
+    fn negotiation_honors_quality_values() {
+        assert_eq!(Encoding::negotiate(None), Encoding::Identity);
+        assert_eq!(
+            Encoding::negotiate(Some("gzip, deflate, br")),
+            Encoding::Brotli
+        );
+        assert_eq!(Encoding::negotiate(Some("br;q=0.5, gzip")), Encoding::Gzip);
+        assert_eq!(Encoding::negotiate(Some("br;q=0, *;q=0.1")), Encoding::Gzip);
+        assert_eq!(
+            Encoding::negotiate(Some("identity, deflate")),
+            Encoding::Identity
+        );
+    }
+
+    #[test]
+    fn compressed_bodies_round_trip() {
+        let body = "<svg>".to_string() + &"<g/>".repeat(500) + "</svg>";
+
+        let gzip = Encoding::Gzip.compress(body.as_bytes()).unwrap();
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(gzip.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+
+        let brotli = Encoding::Brotli.compress(body.as_bytes()).unwrap();
+        let mut decoded = String::new();
+        brotli::Decompressor::new(brotli.as_slice(), 4_096)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+        assert!(brotli.len() < body.len() / 10);
+    }
+}
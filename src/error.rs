@@ -1,18 +1,38 @@
 use thiserror::Error;
 
+use crate::request::ParamError;
+
 #[derive(Debug, Error, Clone)]
 pub enum ServiceError {
     #[error("rate limit exceeded")]
     RateLimit,
+    #[error("too many requests, retry in {retry_after}s")]
+    TooManyRequests { retry_after: u64 },
     #[error("not found")]
     NotFound,
+    #[error("invalid repository")]
+    InvalidRepository,
+    #[error("too many team members")]
+    TeamTooLarge,
+    #[error("invalid comparison")]
+    InvalidComparison,
+    #[error("invalid parameters")]
+    InvalidParameters(Vec<ParamError>),
+    #[error("internal error")]
+    Internal,
 }
 
 impl ServiceError {
     pub fn status_code(&self) -> u16 {
         match self {
             Self::RateLimit => 419,
+            Self::TooManyRequests { .. } => 429,
             Self::NotFound => 404,
+            Self::InvalidRepository => 400,
+            Self::TeamTooLarge => 400,
+            Self::InvalidComparison => 400,
+            Self::InvalidParameters(_) => 400,
+            Self::Internal => 500,
         }
     }
 }
@@ -0,0 +1,142 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use serde::Deserialize;
+
+use crate::i18n::Lang;
+
+// DejaVu Sans Bold cut down to Basic Latin, Latin-1 and common punctuation; see `fonts/LICENSE`.
+const BUILTIN_WOFF2: &[u8] = include_bytes!("fonts/dejavu-sans-bold-latin.woff2");
+// Advance widths, in font units, of every character the WOFF2 holds.
+const BUILTIN_METRICS: &str = include_str!("fonts/dejavu-sans-bold-latin.json");
+
+static FONT: OnceLock<Font> = OnceLock::new();
+
+#[derive(Debug, Deserialize)]
+struct Metrics {
+    units_per_em: u16,
+    advances: HashMap<char, u16>,
+}
+
+// The bundled font as a ready-made data URI, with the metrics used to measure text in it.
+#[derive(Debug)]
+pub struct Font {
+    units_per_em: f64,
+    advances: HashMap<char, u16>,
+    data_uri: String,
+}
+
+pub fn embedded() -> &'static Font {
+    FONT.get_or_init(|| {
+        Font::new(BUILTIN_WOFF2, BUILTIN_METRICS).expect("built-in font metrics must be valid")
+    })
+}
+
+// The WOFF2 is cut down to Latin, so it is only worth its ~13 KB on cards written in Latin
+// script; CJK and Hangul text would fall back to system fonts anyway.
+pub fn covers(lang: Lang) -> bool {
+    matches!(lang, Lang::En | Lang::Es)
+}
+
+impl Font {
+    fn new(woff2: &[u8], metrics: &str) -> Result<Self, serde_json::Error> {
+        let metrics = serde_json::from_str::<Metrics>(metrics)?;
+        Ok(Self {
+            units_per_em: metrics.units_per_em.max(1) as f64,
+            advances: metrics.advances,
+            data_uri: format!("data:font/woff2;base64,{}", base64(woff2)),
+        })
+    }
+
+    // Width in pixels; characters the font lacks are left to the caller's estimate.
+    pub fn text_width(&self, text: &str, font_size: f64) -> Option<f64> {
+        text.chars()
+            .map(|c| self.advances.get(&c).copied().map(f64::from))
+            .sum::<Option<f64>>()
+            .map(|units| units * font_size / self.units_per_em)
+    }
+
+    pub fn data_uri(&self) -> &str {
+        &self.data_uri
+    }
+}
+
+pub fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, byte)| {
+            bits | ((*byte as u32) << (16 - index * 8))
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                output.push(ALPHABET[(bits >> (18 - index * 6)) as usize & 0x3F] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BUILTIN_WOFF2, base64, embedded};
+
+    #[test]
+    fn measures_text_from_bundled_metrics() {
+        let font = embedded();
+        // "A" and "B" advance 1585 and 1561 units on a 2048-unit em.
+        assert_eq!(font.text_width("AB", 2048.0), Some(3146.0));
+        assert!(font.text_width("¿Año…?", 16.0).is_some());
+        assert_eq!(font.text_width("A星", 16.0), None);
+    }
+
+    #[test]
+    fn bundled_font_is_a_woff2_data_uri() {
+        assert_eq!(&BUILTIN_WOFF2[..4], b"wOF2");
+        assert!(
+            embedded()
+                .data_uri()
+                .starts_with("data:font/woff2;base64,d09GMg")
+        );
+        // The README quotes this per-card cost.
+        assert!(embedded().data_uri().len() < 13_500);
+    }
+
+    #[test]
+    fn base64_pads_partial_chunks() {
+        let encode = |text: &str| base64(text.as_bytes());
+        assert_eq!(encode(""), "");
+        assert_eq!(encode("f"), "Zg==");
+        assert_eq!(encode("fo"), "Zm8=");
+        assert_eq!(encode("foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn embedded_cards_carry_the_font_face() {
+        let card = crate::svg::Card::new(
+            Vec::new(),
+            Default::default(),
+            6,
+            3,
+            110,
+            0,
+            0,
+            false,
+            false,
+        );
+        let user_info = crate::models::UserInfo::default();
+
+        let svg = card
+            .clone()
+            .with_font(Some(embedded()))
+            .render(&user_info, &crate::themes::THEME_DEFAULT);
+        assert_eq!(svg.matches("@font-face").count(), 1);
+        assert!(svg.contains("src:url(data:font/woff2;base64,d09GMg"));
+        assert!(svg.contains("text[font-family^=\"Segoe UI\"]"));
+
+        let plain = card.render(&user_info, &crate::themes::THEME_DEFAULT);
+        assert!(!plain.contains("@font-face"));
+    }
+}
@@ -4,11 +4,17 @@ use reqwest::header::{ACCEPT, CONTENT_TYPE, HeaderMap, HeaderValue, USER_AGENT};
 use serde::{Deserialize, de::DeserializeOwned};
 use serde_json::json;
 use tokio::try_join;
+use tracing::warn;
 
 use crate::{
-    constants::{DEFAULT_GITHUB_API, DEFAULT_GITHUB_RETRY_DELAY_MS},
+    constants::{
+        DEFAULT_GITHUB_API, DEFAULT_GITHUB_RETRY_DELAY_MS, MAX_ORGANIZATION_REPOSITORY_PAGES,
+    },
     error::ServiceError,
-    models::{UserActivity, UserInfo, UserIssue, UserPullRequest, UserRepository},
+    models::{
+        OrganizationActivity, OrganizationInfo, RepositoryActivity, RepositoryInfo, UserActivity,
+        UserCommunity, UserInfo, UserIssue, UserPullRequest, UserRepository,
+    },
 };
 
 const QUERY_USER_ACTIVITY: &str = r#"
@@ -109,6 +115,7 @@ query userInfo($username: String!) {
         stargazers {
           totalCount
         }
+        forkCount
         createdAt
       }
     }
@@ -130,6 +137,7 @@ query viewerInfo {
         stargazers {
           totalCount
         }
+        forkCount
         createdAt
       }
     }
@@ -137,6 +145,116 @@ query viewerInfo {
 }
 "#;
 
+const QUERY_USER_COMMUNITY: &str = r#"
+query userInfo($username: String!) {
+  user(login: $username) {
+    gists(first: 1) {
+      totalCount
+    }
+    repositoryDiscussions(first: 1) {
+      totalCount
+    }
+    repositoryDiscussionComments(first: 1, onlyAnswers: true) {
+      totalCount
+    }
+    sponsors(first: 1) {
+      totalCount
+    }
+    sponsoring(first: 1) {
+      totalCount
+    }
+    packages(first: 1) {
+      totalCount
+    }
+  }
+}
+"#;
+
+const QUERY_VIEWER_COMMUNITY: &str = r#"
+query viewerInfo {
+  user: viewer {
+    gists(first: 1, privacy: ALL) {
+      totalCount
+    }
+    repositoryDiscussions(first: 1) {
+      totalCount
+    }
+    repositoryDiscussionComments(first: 1, onlyAnswers: true) {
+      totalCount
+    }
+    sponsors(first: 1) {
+      totalCount
+    }
+    sponsoring(first: 1) {
+      totalCount
+    }
+    packages(first: 1) {
+      totalCount
+    }
+  }
+}
+"#;
+
+const QUERY_ORGANIZATION: &str = r#"
+query organizationInfo($username: String!, $after: String) {
+  user: organization(login: $username) {
+    createdAt
+    membersWithRole(first: 1) {
+      totalCount
+    }
+    sponsors(first: 1) {
+      totalCount
+    }
+    repositories(first: 100, after: $after, privacy: PUBLIC, orderBy: {direction: DESC, field: STARGAZERS}) {
+      totalCount
+      pageInfo {
+        hasNextPage
+        endCursor
+      }
+      nodes {
+        languages(first: 3, orderBy: {direction: DESC, field: SIZE}) {
+          nodes {
+            name
+          }
+        }
+        stargazers {
+          totalCount
+        }
+        createdAt
+      }
+    }
+  }
+}
+"#;
+
+const QUERY_REPOSITORY: &str = r#"
+query repositoryInfo($owner: String!, $name: String!) {
+  user: repository(owner: $owner, name: $name) {
+    createdAt
+    stargazerCount
+    forkCount
+    watchers(first: 1) {
+      totalCount
+    }
+    mentionableUsers(first: 1) {
+      totalCount
+    }
+    releases(first: 1) {
+      totalCount
+    }
+    mergedPullRequests: pullRequests(states: MERGED) {
+      totalCount
+    }
+    closedIssues: issues(states: CLOSED) {
+      totalCount
+    }
+    languages(first: 1) {
+      totalCount
+    }
+  }
+}
+"#;
+
 const QUERY_VIEWER_LOGIN: &str = r#"
 query viewerLogin {
   user: viewer {
@@ -191,18 +309,78 @@ impl GithubClient {
         let activity = self.request_user_activity(username, include_private);
         let issue = self.request_user_issue(username, include_private);
         let pull_request = self.request_user_pull_request(username, include_private);
+        // Community counts are extras: losing them must not cost the whole card.
+        let community = async {
+            Ok(self
+                .request_user_community(username, include_private)
+                .await
+                .unwrap_or_else(|err| {
+                    warn!("community counts unavailable for '{username}': {err}");
+                    UserCommunity::default()
+                }))
+        };
 
-        let (repository, activity, issue, pull_request) =
-            try_join!(repository, activity, issue, pull_request)?;
+        let (repository, activity, issue, pull_request, community) =
+            try_join!(repository, activity, issue, pull_request, community)?;
 
         Ok(UserInfo::from_parts(
             activity,
             issue,
             pull_request,
             repository,
+            community,
         ))
     }
 
+    pub async fn request_organization_info(
+        &self,
+        login: &str,
+    ) -> Result<OrganizationInfo, ServiceError> {
+        let organization_page = |after: Option<String>| {
+            let payload = json!({
+                "query": QUERY_ORGANIZATION,
+                "variables": {
+                    "username": login,
+                    "after": after,
+                }
+            });
+            async move { self.execute_payload::<OrganizationActivity>(&payload).await }
+        };
+
+        let mut organization = organization_page(None).await?;
+        for _ in 1..MAX_ORGANIZATION_REPOSITORY_PAGES {
+            let page_info = &organization.repositories.page_info;
+            if !page_info.has_next_page {
+                break;
+            }
+            let next = organization_page(page_info.end_cursor.clone()).await?;
+            organization.repositories.page_info = next.repositories.page_info;
+            organization
+                .repositories
+                .nodes
+                .extend(next.repositories.nodes);
+        }
+
+        Ok(OrganizationInfo::from_activity(organization))
+    }
+
+    pub async fn request_repository_info(
+        &self,
+        owner: &str,
+        name: &str,
+    ) -> Result<RepositoryInfo, ServiceError> {
+        let payload = json!({
+            "query": QUERY_REPOSITORY,
+            "variables": {
+                "owner": owner,
+                "name": name,
+            }
+        });
+        let repository: RepositoryActivity = self.execute_payload(&payload).await?;
+
+        Ok(RepositoryInfo::from_activity(repository))
+    }
+
     pub async fn request_user_repository(
         &self,
         username: &str,
@@ -253,6 +431,19 @@ impl GithubClient {
         }
     }
 
+    pub async fn request_user_community(
+        &self,
+        username: &str,
+        include_private: bool,
+    ) -> Result<UserCommunity, ServiceError> {
+        if include_private {
+            self.execute_viewer_query(QUERY_VIEWER_COMMUNITY).await
+        } else {
+            self.execute_user_query(QUERY_USER_COMMUNITY, username)
+                .await
+        }
+    }
+
     async fn execute_user_query<T: DeserializeOwned>(
         &self,
         query: &str,
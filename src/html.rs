@@ -1,15 +1,22 @@
-use crate::{error::ServiceError, themes::THEME_NAMES};
+use crate::{
+    constants::MAX_TEAM_MEMBERS, error::ServiceError, i18n::Lang, svg::escape_xml,
+    themes::THEME_NAMES,
+};
 
-pub fn missing_username_page(base_path: &str) -> String {
+pub fn missing_username_page(base_path: &str, lang: Lang) -> String {
     let themes = THEME_NAMES.join(", ");
+    let preview_path = preview_path(base_path);
+    let code = lang.code();
+    let page_title = lang.translate("GitHub Profile Trophy");
+    let t = |text: &'static str| lang.translate(text);
 
     format!(
         r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{code}">
 <head>
   <meta charset="UTF-8" />
   <meta name="viewport" content="width=device-width, initial-scale=1.0" />
-  <title>GitHub Profile Trophy</title>
+  <title>{page_title}</title>
   <style>
     body {{ font-family: Arial, sans-serif; margin: 0; background: #f4f4f4; color: #222; }}
     section {{ width: min(860px, 92vw); margin: 24px auto; }}
@@ -24,45 +31,149 @@ pub fn missing_username_page(base_path: &str) -> String {
 <body>
   <section>
     <div class="card">
-      <h2>"username" is a required query parameter</h2>
-      <p>URL example: <code>{base_path}?username=USERNAME</code></p>
-      <p class="muted">Example themes: {themes}</p>
+      <h2>{}</h2>
+      <p>{} <code>{base_path}?username=USERNAME</code></p>
+      <p class="muted">{} {themes}</p>
     </div>
     <div class="card">
-      <h2>Generate Trophy</h2>
+      <h2>{}</h2>
       <form action="{base_path}" method="get">
-        <label for="username">GitHub Username</label>
+        <label for="username">{}</label>
         <input id="username" name="username" type="text" placeholder="Ex. h-sumiya" required />
 
-        <label for="theme">Theme (optional)</label>
+        <label for="theme">{}</label>
         <input id="theme" name="theme" type="text" placeholder="Ex. onedark" value="default" />
 
-        <button type="submit">Get Trophies</button>
+        <button type="submit">{}</button>
+        <button type="submit" formaction="{preview_path}">{}</button>
       </form>
     </div>
   </section>
 </body>
-</html>"#
+</html>"#,
+        t("\"username\" is a required query parameter"),
+        t("URL example:"),
+        t("Example themes:"),
+        t("Generate Trophy"),
+        t("GitHub Username"),
+        t("Theme (optional)"),
+        t("Get Trophies"),
+        t("Preview")
     )
 }
 
-pub fn error_page(error: &ServiceError) -> String {
+// Shows the card a query renders; the handler only serves it once the query validates.
+pub fn preview_page(card_url: &str, lang: Lang) -> String {
+    let code = lang.code();
+    let page_title = lang.translate("GitHub Profile Trophy");
+    let card_url = escape_xml(card_url);
+    let t = |text: &'static str| lang.translate(text);
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{code}">
+<head>
+  <meta charset="UTF-8" />
+  <meta name="viewport" content="width=device-width, initial-scale=1.0" />
+  <title>{page_title}</title>
+  <style>
+    body {{ font-family: Arial, sans-serif; margin: 0; background: #f4f4f4; color: #222; }}
+    section {{ width: min(860px, 92vw); margin: 24px auto; }}
+    .card {{ background: #fff; border-radius: 8px; padding: 20px; margin-bottom: 16px; box-shadow: 0 4px 24px rgba(0, 0, 0, 0.08); }}
+    img {{ max-width: 100%; }}
+    code {{ display: block; background: #f0f0f0; padding: 8px 10px; border-radius: 4px; overflow-wrap: anywhere; }}
+  </style>
+</head>
+<body>
+  <section>
+    <div class="card">
+      <h2>{}</h2>
+      <img src="{card_url}" alt="{page_title}" />
+    </div>
+    <div class="card">
+      <p>{}</p>
+      <code>![{page_title}]({card_url})</code>
+    </div>
+  </section>
+</body>
+</html>"#,
+        t("Preview"),
+        t("Markdown:"),
+    )
+}
+
+// The preview sits next to the card endpoint, so it also works under a path prefix.
+pub fn preview_path(base_path: &str) -> String {
+    format!("{}/preview", base_path.trim_end_matches('/'))
+}
+
+pub fn error_page(error: &ServiceError, lang: Lang) -> String {
+    let code = lang.code();
+    let page_title = lang.translate("GitHub Profile Trophy");
     let (status, message, detail) = match error {
-        ServiceError::RateLimit => (419, "Rate Limit Exceeded", "Please retry later."),
+        ServiceError::RateLimit => (
+            419,
+            "Rate Limit Exceeded",
+            lang.translate("Please retry later.").to_string(),
+        ),
+        ServiceError::TooManyRequests { retry_after } => (
+            429,
+            "Too Many Requests",
+            lang.format(
+                "Please retry in {seconds} seconds.",
+                &[("seconds", retry_after)],
+            ),
+        ),
         ServiceError::NotFound => (
             404,
             "Not Found",
-            "Sorry, the user you are looking for was not found.",
+            lang.translate("Sorry, the user you are looking for was not found.")
+                .to_string(),
+        ),
+        ServiceError::InvalidRepository => (
+            400,
+            "Bad Request",
+            lang.translate("The repo parameter must be in the form owner/name.")
+                .to_string(),
+        ),
+        ServiceError::TeamTooLarge => (
+            400,
+            "Bad Request",
+            lang.format(
+                "A team card supports at most {count} members.",
+                &[("count", &MAX_TEAM_MEMBERS)],
+            ),
+        ),
+        ServiceError::InvalidComparison => (
+            400,
+            "Bad Request",
+            lang.translate("The compare parameter must name exactly two users, e.g. alice,bob.")
+                .to_string(),
+        ),
+        ServiceError::InvalidParameters(errors) => (
+            400,
+            "Bad Request",
+            errors.iter().fold(
+                lang.translate("Some query parameters are invalid:")
+                    .to_string(),
+                |detail, error| format!("{detail}<br />{}", escape_xml(&error.describe(lang))),
+            ),
+        ),
+        ServiceError::Internal => (
+            500,
+            "Internal Server Error",
+            lang.translate("Something went wrong while building the card.")
+                .to_string(),
         ),
     };
 
     format!(
         r#"<!DOCTYPE html>
-<html lang="en">
+<html lang="{code}">
 <head>
   <meta charset="UTF-8" />
   <meta name="viewport" content="width=device-width, initial-scale=1.0" />
-  <title>GitHub Profile Trophy</title>
+  <title>{page_title}</title>
   <style>
     body {{ font-family: Arial, sans-serif; margin: 0; background: #f4f4f4; color: #222; }}
     section {{ width: min(760px, 92vw); margin: 48px auto; }}
@@ -72,11 +183,64 @@ pub fn error_page(error: &ServiceError) -> String {
 <body>
   <section>
     <div class="card">
-      <h1>{status} - {message}</h1>
+      <h1>{status} - {}</h1>
       <p>{detail}</p>
     </div>
   </section>
 </body>
-</html>"#
+</html>"#,
+        lang.translate(message),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{error_page, missing_username_page, preview_page, preview_path};
+    use crate::{error::ServiceError, i18n::Lang, request::ParamError};
+
+    #[test]
+    fn pages_use_requested_language() {
+        let page = missing_username_page("/", Lang::Ja);
+        assert!(page.contains("<html lang=\"ja\">"));
+        assert!(page.contains("<h2>トロフィーを生成</h2>"));
+
+        let page = error_page(&ServiceError::TeamTooLarge, Lang::Es);
+        assert!(page.contains("<html lang=\"es\">"));
+        assert!(page.contains("400 - Solicitud incorrecta"));
+        assert!(page.contains("como máximo 10 miembros"));
+
+        let page = error_page(
+            &ServiceError::InvalidParameters(vec![ParamError::InvalidRank {
+                value: "<S>".into(),
+            }]),
+            Lang::En,
+        );
+        assert!(page.contains("&apos;&lt;S&gt;&apos; is not a valid rank filter"));
+
+        let page = error_page(&ServiceError::TooManyRequests { retry_after: 30 }, Lang::En);
+        assert!(page.contains("429 - Too Many Requests"));
+        assert!(page.contains("Please retry in 30 seconds."));
+
+        let page = error_page(&ServiceError::Internal, Lang::Ja);
+        assert!(page.contains("500 - サーバー内部エラー"));
+
+        let errors = vec![ParamError::InvalidBoolean {
+            name: "no-bg",
+            value: "<1>".into(),
+        }];
+        let page = error_page(&ServiceError::InvalidParameters(errors), Lang::Ja);
+        assert!(page.contains("<br />no-bg には true か false を指定してください"));
+        assert!(page.contains("&lt;1&gt;"));
+    }
+
+    #[test]
+    fn preview_links_the_card_next_to_the_form() {
+        assert_eq!(preview_path("/"), "/preview");
+        assert_eq!(preview_path("/trophy/"), "/trophy/preview");
+        assert!(missing_username_page("/", Lang::En).contains("formaction=\"/preview\""));
+
+        let page = preview_page("/?username=a&title=\"x\"", Lang::Es);
+        assert!(page.contains("<h2>Vista previa</h2>"));
+        assert!(page.contains("src=\"/?username=a&amp;title=&quot;x&quot;\""));
+    }
+}
@@ -0,0 +1,142 @@
+use std::{collections::HashMap, fmt::Display, sync::OnceLock};
+
+type Catalog = HashMap<String, String>;
+
+const CATALOG_SOURCES: [(Lang, &str); 4] = [
+    (Lang::Ja, include_str!("locales/ja.json")),
+    (Lang::Ko, include_str!("locales/ko.json")),
+    (Lang::Zh, include_str!("locales/zh.json")),
+    (Lang::Es, include_str!("locales/es.json")),
+];
+
+static CATALOGS: OnceLock<HashMap<Lang, Catalog>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Lang {
+    #[default]
+    En,
+    Ja,
+    Ko,
+    Zh,
+    Es,
+}
+
+impl Lang {
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Ja => "ja",
+            Self::Ko => "ko",
+            Self::Zh => "zh",
+            Self::Es => "es",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        let primary = code.trim().split(['-', '_']).next()?;
+        [Self::En, Self::Ja, Self::Ko, Self::Zh, Self::Es]
+            .into_iter()
+            .find(|lang| lang.code().eq_ignore_ascii_case(primary))
+    }
+
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut candidates = header
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let code = parts.next()?.trim();
+                let quality = parts
+                    .filter_map(|part| part.trim().strip_prefix("q="))
+                    .find_map(|value| value.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (quality > 0.0).then_some((code, quality))
+            })
+            .collect::<Vec<_>>();
+        candidates.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+
+        candidates
+            .into_iter()
+            .find_map(|(code, _)| Self::from_code(code))
+    }
+
+    pub fn translate(self, text: &str) -> &str {
+        catalogs()
+            .get(&self)
+            .and_then(|catalog| catalog.get(text))
+            .map_or(text, String::as_str)
+    }
+
+    pub fn format(self, template: &str, args: &[(&str, &dyn Display)]) -> String {
+        args.iter().fold(
+            self.translate(template).to_string(),
+            |text, (name, value)| text.replace(&format!("{{{name}}}"), &value.to_string()),
+        )
+    }
+}
+
+fn catalogs() -> &'static HashMap<Lang, Catalog> {
+    CATALOGS.get_or_init(|| {
+        CATALOG_SOURCES
+            .iter()
+            .map(|(lang, source)| {
+                let catalog =
+                    serde_json::from_str(source).expect("built-in catalogs must be valid");
+                (*lang, catalog)
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lang, catalogs};
+    use crate::definitions::definitions;
+
+    #[test]
+    fn accept_language_prefers_highest_quality_supported_tag() {
+        assert_eq!(
+            Lang::from_accept_language("fr-FR, ja;q=0.8, ko-KR;q=0.9"),
+            Some(Lang::Ko)
+        );
+        assert_eq!(Lang::from_accept_language("es;q=0, de"), None);
+        assert_eq!(Lang::from_code("zh-Hant"), Some(Lang::Zh));
+    }
+
+    #[test]
+    fn translate_falls_back_to_source_text() {
+        assert_eq!(Lang::Ja.translate("Stars"), "スター");
+        assert_eq!(Lang::Ja.translate("Custom Trophy"), "Custom Trophy");
+        assert_eq!(Lang::En.translate("Stars"), "Stars");
+        assert_eq!(
+            Lang::Es.format("{years}y {months}m", &[("years", &7), ("months", &3)]),
+            "7a 3m"
+        );
+    }
+
+    #[test]
+    fn catalogs_cover_builtin_trophy_text() {
+        let definitions = definitions();
+        let texts = [
+            &definitions.user,
+            &definitions.organization,
+            &definitions.repository,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|definition| {
+            definition
+                .rank_conditions
+                .iter()
+                .map(|condition| condition.message)
+                .chain([definition.title])
+                .chain(definition.bottom_override)
+        })
+        .collect::<Vec<_>>();
+
+        for (lang, catalog) in catalogs() {
+            for text in &texts {
+                assert!(catalog.contains_key(*text), "{lang:?} is missing '{text}'");
+            }
+        }
+    }
+}
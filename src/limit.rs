@@ -0,0 +1,376 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv6Addr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use axum::http::HeaderMap;
+
+// Idle buckets are swept after this many new clients, keeping the sweep cost amortized.
+const PRUNE_INTERVAL: usize = 4_096;
+// Past this many tracked clients, newcomers share one overflow bucket.
+const MAX_CLIENTS: usize = 100_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct RateLimitConfig {
+    pub requests_per_minute: Option<u32>,
+    pub burst: Option<u32>,
+    pub trusted_proxies: Vec<IpRange>,
+    pub username_limit: Option<usize>,
+    pub username_window: Duration,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    clients: Option<ClientBuckets>,
+    usernames: Option<UsernameWindow>,
+    trusted_proxies: Vec<IpRange>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let clients = config
+            .requests_per_minute
+            .filter(|rate| *rate > 0)
+            .map(|rate| ClientBuckets {
+                refill_per_second: f64::from(rate) / 60.0,
+                capacity: f64::from(config.burst.filter(|burst| *burst > 0).unwrap_or(rate)),
+                buckets: Mutex::new(Buckets::default()),
+            });
+        let usernames = config
+            .username_limit
+            .filter(|limit| *limit > 0)
+            .map(|limit| UsernameWindow {
+                limit,
+                length: config.username_window.max(Duration::from_secs(1)),
+                seen: Mutex::new((None, HashSet::new())),
+            });
+
+        Self {
+            clients,
+            usernames,
+            trusted_proxies: config.trusted_proxies,
+        }
+    }
+
+    // The requesting client: the peer itself, or when the peer is a trusted proxy, the nearest
+    // untrusted hop in `X-Forwarded-For`.
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = peer.to_canonical();
+        if !self.is_trusted(peer) {
+            return peer;
+        }
+
+        let hops = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+
+        // Hops are appended left to right, so only the right end is vouched for.
+        let mut client = peer;
+        for hop in hops.into_iter().rev() {
+            let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+                break;
+            };
+            client = hop.to_canonical();
+            if !self.is_trusted(client) {
+                break;
+            }
+        }
+        client
+    }
+
+    // Takes one token from the client's bucket, or returns how long until one is available.
+    pub fn check_client(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        match &self.clients {
+            Some(clients) => clients.take(client, now),
+            None => Ok(()),
+        }
+    }
+
+    // Admits a username whose data is not cached; names already seen this window stay free.
+    pub fn check_username(&self, username: &str, now: Instant) -> Result<(), Duration> {
+        match &self.usernames {
+            Some(usernames) => usernames.admit(&username.to_ascii_lowercase(), now),
+            None => Ok(()),
+        }
+    }
+
+    fn is_trusted(&self, address: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|range| range.contains(address))
+    }
+}
+
+#[derive(Debug)]
+struct ClientBuckets {
+    refill_per_second: f64,
+    capacity: f64,
+    buckets: Mutex<Buckets>,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    tokens: HashMap<IpAddr, (f64, Instant)>,
+    inserted: usize,
+}
+
+impl ClientBuckets {
+    fn take(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+        let Buckets { tokens, inserted } = &mut *buckets;
+
+        let mut key = bucket_key(client);
+        if !tokens.contains_key(&key) {
+            *inserted += 1;
+            if *inserted >= PRUNE_INTERVAL {
+                // A bucket left alone long enough to refill is no different from a new one.
+                let full_after = Duration::from_secs_f64(self.capacity / self.refill_per_second);
+                tokens
+                    .retain(|_, (_, updated)| now.saturating_duration_since(*updated) < full_after);
+                *inserted = 0;
+            }
+            if tokens.len() >= MAX_CLIENTS {
+                key = IpAddr::V6(Ipv6Addr::UNSPECIFIED);
+            }
+        }
+
+        let (tokens, updated) = tokens.entry(key).or_insert((self.capacity, now));
+        let elapsed = now.saturating_duration_since(*updated).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.refill_per_second).min(self.capacity);
+        *updated = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - *tokens) / self.refill_per_second,
+            ))
+        }
+    }
+}
+
+// IPv6 clients usually hold a whole /64, so they share one bucket per prefix.
+fn bucket_key(client: IpAddr) -> IpAddr {
+    match client {
+        IpAddr::V6(address) => {
+            IpAddr::V6(Ipv6Addr::from_bits(address.to_bits() & (u128::MAX << 64)))
+        }
+        v4 => v4,
+    }
+}
+
+#[derive(Debug)]
+struct UsernameWindow {
+    limit: usize,
+    length: Duration,
+    seen: Mutex<(Option<Instant>, HashSet<String>)>,
+}
+
+impl UsernameWindow {
+    fn admit(&self, username: &str, now: Instant) -> Result<(), Duration> {
+        let mut seen = self.seen.lock().unwrap_or_else(|err| err.into_inner());
+        let (started, usernames) = &mut *seen;
+
+        let start = match started {
+            Some(start) if now.saturating_duration_since(*start) < self.length => *start,
+            _ => {
+                usernames.clear();
+                *started.insert(now)
+            }
+        };
+
+        if usernames.contains(username) || usernames.len() < self.limit {
+            usernames.insert(username.to_string());
+            Ok(())
+        } else {
+            Err(self.length - now.saturating_duration_since(start))
+        }
+    }
+}
+
+// An address or CIDR block such as `10.0.0.0/8` or `::1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix: u32,
+}
+
+impl IpRange {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (address, prefix) = match value.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix.parse::<u32>().ok()?)),
+            None => (value.trim(), None),
+        };
+        let network = address.parse::<IpAddr>().ok()?.to_canonical();
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(bits);
+
+        (prefix <= bits).then_some(Self { network, prefix })
+    }
+
+    fn contains(&self, address: IpAddr) -> bool {
+        // IPv4 addresses sit in the top bits so one prefix mask serves both families.
+        let bits = |address: IpAddr| match address {
+            IpAddr::V4(address) => u128::from(address.to_bits()) << 96,
+            IpAddr::V6(address) => address.to_bits(),
+        };
+        let mask = u128::MAX.checked_shl(128 - self.prefix).unwrap_or(0);
+
+        self.network.is_ipv4() == address.is_ipv4()
+            && (bits(self.network) ^ bits(address)) & mask == 0
+    }
+}
+
+// Whole seconds for a `Retry-After` header, never rounding a wait down to zero.
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    (wait.as_secs() + u64::from(wait.subsec_nanos() > 0)).max(1)
+}
+
+pub fn parse_trusted_proxies(raw: &str) -> Vec<IpRange> {
+    raw.split(',')
+        .filter(|value| !value.trim().is_empty())
+        .filter_map(IpRange::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::IpAddr,
+        time::{Duration, Instant},
+    };
+
+    use axum::http::{HeaderMap, HeaderValue};
+
+    use super::{
+        IpRange, MAX_CLIENTS, PRUNE_INTERVAL, RateLimitConfig, RateLimiter, parse_trusted_proxies,
+    };
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn token_buckets_refill_per_client() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_minute: Some(60),
+            burst: Some(2),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        assert!(limiter.check_client(ip("192.0.2.1"), now).is_ok());
+        assert!(limiter.check_client(ip("192.0.2.1"), now).is_ok());
+        let retry = limiter.check_client(ip("192.0.2.1"), now).unwrap_err();
+        assert_eq!(retry, Duration::from_secs(1));
+        assert!(limiter.check_client(ip("192.0.2.2"), now).is_ok());
+
+        let later = now + Duration::from_millis(1_500);
+        assert!(limiter.check_client(ip("192.0.2.1"), later).is_ok());
+        assert!(limiter.check_client(ip("192.0.2.1"), later).is_err());
+
+        assert!(limiter.check_client(ip("2001:db8::1"), now).is_ok());
+        assert!(limiter.check_client(ip("2001:db8::2:3"), now).is_ok());
+        assert!(limiter.check_client(ip("2001:db8::ffff"), now).is_err());
+        assert!(limiter.check_client(ip("2001:db8:0:1::1"), now).is_ok());
+
+        let unlimited = RateLimiter::new(RateLimitConfig::default());
+        assert!((0..100).all(|_| unlimited.check_client(ip("192.0.2.1"), now).is_ok()));
+    }
+
+    #[test]
+    fn bucket_map_is_pruned_and_capped() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            requests_per_minute: Some(60),
+            burst: Some(1),
+            ..Default::default()
+        });
+        let clients = limiter.clients.as_ref().unwrap();
+        let now = Instant::now();
+        let client = |n: u32| IpAddr::from(n.to_be_bytes());
+
+        for n in 0..MAX_CLIENTS as u32 {
+            assert!(limiter.check_client(client(n), now).is_ok());
+        }
+        assert_eq!(clients.buckets.lock().unwrap().tokens.len(), MAX_CLIENTS);
+
+        // Newcomers past the cap share the overflow bucket.
+        let extra = MAX_CLIENTS as u32;
+        assert!(limiter.check_client(client(extra), now).is_ok());
+        assert!(limiter.check_client(client(extra + 1), now).is_err());
+        assert_eq!(
+            clients.buckets.lock().unwrap().tokens.len(),
+            MAX_CLIENTS + 1
+        );
+
+        // Once the old buckets have refilled, the next sweep drops them.
+        let later = now + Duration::from_secs(2);
+        for n in 0..PRUNE_INTERVAL as u32 {
+            let _ = limiter.check_client(client(extra + 2 + n), later);
+        }
+        assert!(clients.buckets.lock().unwrap().tokens.len() < PRUNE_INTERVAL);
+        assert!(limiter.check_client(client(1), later).is_ok());
+        assert!(limiter.check_client(client(2), later).is_ok());
+    }
+
+    #[test]
+    fn forwarded_for_is_only_honored_from_trusted_proxies() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            trusted_proxies: parse_trusted_proxies("10.0.0.0/8, ::1"),
+            ..Default::default()
+        });
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.7, 203.0.113.9"),
+        );
+        headers.append("x-forwarded-for", HeaderValue::from_static("10.1.2.3"));
+
+        assert_eq!(
+            limiter.client_ip(ip("10.0.0.1"), &headers),
+            ip("203.0.113.9")
+        );
+        assert_eq!(limiter.client_ip(ip("::1"), &headers), ip("203.0.113.9"));
+        assert_eq!(
+            limiter.client_ip(ip("192.0.2.1"), &headers),
+            ip("192.0.2.1")
+        );
+        assert_eq!(
+            limiter.client_ip(ip("::ffff:10.0.0.1"), &HeaderMap::new()),
+            ip("10.0.0.1")
+        );
+
+        assert_eq!(IpRange::parse("10.0.0.0/33"), None);
+        assert!(IpRange::parse("0.0.0.0/0").unwrap().contains(ip("8.8.8.8")));
+        assert!(!IpRange::parse("fd00::/8").unwrap().contains(ip("fe80::1")));
+    }
+
+    #[test]
+    fn distinct_usernames_are_capped_per_window() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            username_limit: Some(2),
+            username_window: Duration::from_secs(60),
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        assert!(limiter.check_username("alice", now).is_ok());
+        assert!(limiter.check_username("bob", now).is_ok());
+        assert!(limiter.check_username("Alice", now).is_ok());
+        let retry = limiter
+            .check_username("carol", now + Duration::from_secs(45))
+            .unwrap_err();
+        assert_eq!(retry, Duration::from_secs(15));
+        assert!(
+            limiter
+                .check_username("carol", now + Duration::from_secs(60))
+                .is_ok()
+        );
+    }
+}
@@ -1,43 +1,199 @@
 mod constants;
+mod definitions;
+mod encoding;
 mod error;
+mod font;
 mod github;
 mod html;
+mod i18n;
+mod limit;
+mod minify;
 mod models;
+mod number;
 mod params;
+mod request;
 mod svg;
 mod themes;
 mod trophy;
 
-use std::{env, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    env,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use axum::{
     Router,
     body::{Body, Bytes},
-    extract::{OriginalUri, RawQuery, State},
-    http::{HeaderValue, StatusCode, header},
+    extract::{ConnectInfo, OriginalUri, RawQuery, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
     routing::get,
 };
+use chrono::{DateTime, Utc};
 use constants::{
-    CACHE_MAX_AGE, CDN_CACHE_MAX_AGE, DEFAULT_MARGIN_H, DEFAULT_MARGIN_W, DEFAULT_MAX_COLUMN,
-    DEFAULT_MAX_ROW, DEFAULT_NO_BACKGROUND, DEFAULT_NO_FRAME, DEFAULT_PANEL_SIZE,
-    STALE_WHILE_REVALIDATE, SVG_CACHE_TTL_SECS, USER_CACHE_TTL_SECS,
+    CACHE_MAX_AGE, CDN_CACHE_MAX_AGE, MAX_TEAM_MEMBERS, STALE_WHILE_REVALIDATE, SVG_CACHE_TTL_SECS,
+    USER_CACHE_TTL_SECS, USERNAME_LIMIT_WINDOW_SECS,
 };
+use encoding::Encoding;
 use error::ServiceError;
 use github::GithubClient;
+use i18n::Lang;
+use limit::{RateLimitConfig, RateLimiter, parse_trusted_proxies, retry_after_secs};
+use minify::minify_svg;
 use moka::future::Cache;
 use params::ParsedParams;
-use svg::Card;
-use themes::resolve_theme;
+use request::CardRequest;
+use tokio::task::JoinSet;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{error, info, warn};
+use url::form_urlencoded;
+
+enum TrophyTarget {
+    User {
+        username: String,
+        include_private: bool,
+    },
+    Team {
+        name: Option<String>,
+        usernames: Vec<String>,
+    },
+    Comparison {
+        left: String,
+        right: String,
+    },
+    Organization {
+        login: String,
+    },
+    Repository {
+        owner: String,
+        name: String,
+    },
+}
+
+impl TrophyTarget {
+    // Usernames are case-insensitive on GitHub, except in comparisons where they are displayed.
+    fn canonical_params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::User { username, .. } => vec![("username", username.to_lowercase())],
+            Self::Team {
+                name: Some(name), ..
+            } => vec![("team", name.to_lowercase())],
+            Self::Team { usernames, .. } => {
+                let mut usernames = usernames
+                    .iter()
+                    .map(|username| username.to_lowercase())
+                    .collect::<Vec<_>>();
+                usernames.sort();
+                vec![("username", usernames.join(","))]
+            }
+            Self::Comparison { left, right } => vec![("compare", format!("{left},{right}"))],
+            Self::Organization { login } => {
+                vec![("username", login.to_lowercase()), ("type", "org".into())]
+            }
+            Self::Repository { owner, name } => {
+                vec![("repo", format!("{owner}/{name}").to_lowercase())]
+            }
+        }
+    }
+}
+
+// The language of an HTML page, and whether it was negotiated from `Accept-Language`.
+#[derive(Debug, Clone, Copy, Default)]
+struct PageLang {
+    lang: Lang,
+    negotiated: bool,
+}
+
+// GitHub data together with when it was fetched, which becomes the card's `Last-Modified`.
+#[derive(Debug)]
+struct Fetched<T> {
+    data: Arc<T>,
+    fetched_at: DateTime<Utc>,
+}
+
+impl<T> Clone for Fetched<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            fetched_at: self.fetched_at,
+        }
+    }
+}
+
+impl<T> Fetched<T> {
+    fn new(data: T) -> Self {
+        Self {
+            data: Arc::new(data),
+            fetched_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedSvg {
+    body: Bytes,
+    gzip: Option<Bytes>,
+    brotli: Option<Bytes>,
+    etag: String,
+    last_modified: DateTime<Utc>,
+}
+
+impl CachedSvg {
+    // Minifies once and keeps pre-compressed variants, so cache hits never recompress.
+    fn new(svg: String, last_modified: DateTime<Utc>) -> Self {
+        let svg = minify_svg(&svg);
+        let compress = |encoding: Encoding| {
+            encoding
+                .compress(svg.as_bytes())
+                .filter(|compressed| compressed.len() < svg.len())
+                .map(Bytes::from)
+        };
+
+        Self {
+            gzip: compress(Encoding::Gzip),
+            brotli: compress(Encoding::Brotli),
+            etag: etag(svg.as_bytes()),
+            body: Bytes::from(svg),
+            last_modified,
+        }
+    }
+
+    fn variant(&self, encoding: Encoding) -> (Encoding, Bytes) {
+        let compressed = match encoding {
+            Encoding::Identity => None,
+            Encoding::Gzip => self.gzip.clone(),
+            Encoding::Brotli => self.brotli.clone(),
+        };
+
+        match compressed {
+            Some(body) => (encoding, body),
+            None => (Encoding::Identity, self.body.clone()),
+        }
+    }
+
+    // Each representation needs its own strong tag.
+    fn variant_etag(&self, encoding: Encoding) -> String {
+        match encoding.content_encoding() {
+            Some(coding) => format!("{}-{coding}\"", self.etag.trim_end_matches('"')),
+            None => self.etag.clone(),
+        }
+    }
+}
 
 #[derive(Clone)]
 struct AppState {
     github: Arc<GithubClient>,
     default_username: Option<String>,
-    user_cache: Cache<String, Arc<models::UserInfo>>,
-    svg_cache: Cache<String, Bytes>,
+    teams: Arc<HashMap<String, Vec<String>>>,
+    canonical_redirect: bool,
+    rate_limiter: Arc<RateLimiter>,
+    user_cache: Cache<String, Fetched<models::UserInfo>>,
+    organization_cache: Cache<String, Fetched<models::OrganizationInfo>>,
+    repository_cache: Cache<String, Fetched<models::RepositoryInfo>>,
+    svg_cache: Cache<String, CachedSvg>,
 }
 
 #[tokio::main]
@@ -55,6 +211,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .and_then(|value| value.parse::<u16>().ok())
         .unwrap_or(8080);
 
+    if let Ok(path) = env::var("TROPHY_DEFINITIONS")
+        && !path.trim().is_empty()
+    {
+        let loaded = definitions::init_from_file(&path)?;
+        info!(
+            "loaded trophy definitions from '{path}' ({} user, {} organization, {} repository)",
+            loaded.user.len(),
+            loaded.organization.len(),
+            loaded.repository.len()
+        );
+    }
+
     let github_api = env::var("GITHUB_API").ok();
     let mut tokens = vec![
         env::var("GITHUB_TOKEN1").ok(),
@@ -94,11 +262,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         None
     };
 
+    let teams = env::var("TEAMS")
+        .map(|raw| parse_teams(&raw))
+        .unwrap_or_default();
+    if !teams.is_empty() {
+        info!("loaded {} team definitions", teams.len());
+    }
+
+    let canonical_redirect = env::var("CANONICAL_REDIRECT").is_ok_and(|value| value == "true");
+
+    let rate_limiter = RateLimiter::new(RateLimitConfig {
+        requests_per_minute: env_number("RATE_LIMIT_PER_MINUTE"),
+        burst: env_number("RATE_LIMIT_BURST"),
+        trusted_proxies: env::var("TRUSTED_PROXIES")
+            .map(|raw| parse_trusted_proxies(&raw))
+            .unwrap_or_default(),
+        username_limit: env_number("USERNAME_LIMIT"),
+        username_window: Duration::from_secs(
+            env_number("USERNAME_LIMIT_WINDOW_SECS").unwrap_or(USERNAME_LIMIT_WINDOW_SECS),
+        ),
+    });
+
     let user_cache = Cache::builder()
         .max_capacity(20_000)
         .time_to_live(Duration::from_secs(USER_CACHE_TTL_SECS))
         .build();
 
+    let organization_cache = Cache::builder()
+        .max_capacity(5_000)
+        .time_to_live(Duration::from_secs(USER_CACHE_TTL_SECS))
+        .build();
+
+    let repository_cache = Cache::builder()
+        .max_capacity(5_000)
+        .time_to_live(Duration::from_secs(USER_CACHE_TTL_SECS))
+        .build();
+
     let svg_cache = Cache::builder()
         .max_capacity(20_000)
         .time_to_live(Duration::from_secs(SVG_CACHE_TTL_SECS))
@@ -107,12 +306,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let state = AppState {
         github,
         default_username,
+        teams: Arc::new(teams),
+        canonical_redirect,
+        rate_limiter: Arc::new(rate_limiter),
         user_cache,
+        organization_cache,
+        repository_cache,
         svg_cache,
     };
 
     let app = Router::new()
         .route("/", get(index_handler))
+        .route("/preview", get(preview_handler))
         .route("/healthz", get(health_handler))
         .layer(
             CorsLayer::new()
@@ -125,9 +330,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::UNSPECIFIED, port)).await?;
     info!("listening on 0.0.0.0:{port}");
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
@@ -136,95 +344,340 @@ async fn index_handler(
     State(state): State<AppState>,
     RawQuery(raw_query): RawQuery,
     OriginalUri(uri): OriginalUri,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
 ) -> Response {
     let params = ParsedParams::from_raw(raw_query.as_deref());
+    let page_lang = page_lang(&params, &headers);
+    let request = CardRequest::parse(&params);
 
-    let username = match resolve_username(
-        params.get_optional_string("username"),
-        state.default_username.as_deref(),
-    ) {
-        Some(value) => value,
-        None => {
-            let body = html::missing_username_page(uri.path());
-            return html_response(StatusCode::BAD_REQUEST, body);
+    // Every response counts against the client, error pages included.
+    let client = state.rate_limiter.client_ip(peer.ip(), &headers);
+    if let Err(wait) = state.rate_limiter.check_client(client, Instant::now()) {
+        warn!("rate limited client {client}");
+        let retry_after = retry_after_secs(wait);
+        return rate_limited_response(&request, retry_after);
+    }
+
+    if request.strict && !request.errors.is_empty() {
+        return error_response(
+            ServiceError::InvalidParameters(request.errors.clone()),
+            page_lang,
+        );
+    }
+
+    let target = if let Some(repo) = params.get_optional_string("repo") {
+        match parse_repository(&repo) {
+            Some((owner, name)) => TrophyTarget::Repository { owner, name },
+            None => return error_response(ServiceError::InvalidRepository, page_lang),
+        }
+    } else if let Some(compare) = params.get_optional_string("compare") {
+        match <[String; 2]>::try_from(split_usernames(&compare)) {
+            Ok([left, right]) => TrophyTarget::Comparison { left, right },
+            Err(_) => return error_response(ServiceError::InvalidComparison, page_lang),
+        }
+    } else if let Some(team) = params.get_optional_string("team") {
+        match state.teams.get(&team.to_ascii_lowercase()) {
+            Some(usernames) => TrophyTarget::Team {
+                name: Some(team),
+                usernames: usernames.clone(),
+            },
+            None => return error_response(ServiceError::NotFound, page_lang),
+        }
+    } else {
+        let username = match resolve_username(
+            params.get_optional_string("username"),
+            state.default_username.as_deref(),
+        ) {
+            Some(value) => value,
+            None => {
+                let body = html::missing_username_page(uri.path(), page_lang.lang);
+                return html_response(StatusCode::BAD_REQUEST, body, page_lang);
+            }
+        };
+
+        if params
+            .get_string_value("type", "user")
+            .eq_ignore_ascii_case("org")
+        {
+            TrophyTarget::Organization { login: username }
+        } else {
+            let mut usernames = split_usernames(&username);
+            if usernames.len() > 1 {
+                TrophyTarget::Team {
+                    name: None,
+                    usernames,
+                }
+            } else {
+                let username = usernames.pop().unwrap_or(username);
+                let include_private =
+                    should_include_private(state.default_username.as_deref(), &username);
+                TrophyTarget::User {
+                    username,
+                    include_private,
+                }
+            }
         }
     };
-    let include_private = should_include_private(state.default_username.as_deref(), &username);
 
-    let row = params.get_number_value("row", DEFAULT_MAX_ROW).max(1);
-    let mut column = params.get_number_value("column", DEFAULT_MAX_COLUMN);
-    if column != -1 && column < 1 {
-        column = DEFAULT_MAX_COLUMN;
+    if let TrophyTarget::Team { usernames, .. } = &target
+        && usernames.len() > MAX_TEAM_MEMBERS
+    {
+        return error_response(ServiceError::TeamTooLarge, page_lang);
     }
 
-    let theme_name = params.get_string_value("theme", "default");
-    let theme = resolve_theme(&theme_name);
-
-    let margin_width = params.get_number_value("margin-w", DEFAULT_MARGIN_W);
-    let margin_height = params.get_number_value("margin-h", DEFAULT_MARGIN_H);
-    let no_background = params.get_boolean_value("no-bg", DEFAULT_NO_BACKGROUND);
-    let no_frame = params.get_boolean_value("no-frame", DEFAULT_NO_FRAME);
-    let titles = params.get_all_csv("title");
-    let ranks = params.get_all_csv("rank");
+    let query = canonical_query(&target, &request);
+    if state.canonical_redirect && raw_query.as_deref().unwrap_or_default() != query {
+        return redirect_response(&format!("{}?{query}", uri.path()));
+    }
 
-    let request_cache_key = cache_key(uri.path(), raw_query.as_deref());
+    let request_cache_key = cache_key(uri.path(), &query);
     if let Some(svg) = state.svg_cache.get(&request_cache_key).await {
-        return svg_response(svg);
+        return svg_response(svg, &headers);
     }
 
-    let user_key_cache = format!("v2-{username}-private={include_private}");
-    let user_info = if let Some(cached) = state.user_cache.get(&user_key_cache).await {
-        cached
-    } else {
-        match state
-            .github
-            .request_user_info(&username, include_private)
+    let card = request.card();
+    let theme = request.theme;
+
+    let rendered = match &target {
+        TrophyTarget::User {
+            username,
+            include_private,
+        } => fetch_user_info(&state, username, *include_private)
             .await
-        {
-            Ok(user_info) => {
-                let user_info = Arc::new(user_info);
-                state
-                    .user_cache
-                    .insert(user_key_cache.clone(), user_info.clone())
-                    .await;
-                user_info
-            }
-            Err(err) => {
-                error!("GitHub API error for username='{username}': {err}");
-                return error_response(err);
-            }
+            .map(|user_info| {
+                CachedSvg::new(card.render(&user_info.data, theme), user_info.fetched_at)
+            }),
+        TrophyTarget::Team { usernames, .. } => {
+            fetch_team_info(&state, usernames).await.map(|team_info| {
+                CachedSvg::new(card.render(&team_info.data, theme), team_info.fetched_at)
+            })
+        }
+        TrophyTarget::Comparison { left, right } => {
+            let include_left = should_include_private(state.default_username.as_deref(), left);
+            let include_right = should_include_private(state.default_username.as_deref(), right);
+            tokio::try_join!(
+                fetch_user_info(&state, left, include_left),
+                fetch_user_info(&state, right, include_right),
+            )
+            .map(|(left_info, right_info)| {
+                CachedSvg::new(
+                    card.render_comparison(
+                        (left, &left_info.data),
+                        (right, &right_info.data),
+                        theme,
+                    ),
+                    left_info.fetched_at.max(right_info.fetched_at),
+                )
+            })
+        }
+        TrophyTarget::Organization { login } => {
+            fetch_organization_info(&state, login)
+                .await
+                .map(|organization_info| {
+                    CachedSvg::new(
+                        card.render_organization(&organization_info.data, theme),
+                        organization_info.fetched_at,
+                    )
+                })
+        }
+        TrophyTarget::Repository { owner, name } => fetch_repository_info(&state, owner, name)
+            .await
+            .map(|repository_info| {
+                CachedSvg::new(
+                    card.render_repository(&repository_info.data, theme),
+                    repository_info.fetched_at,
+                )
+            }),
+    };
+    let svg = match rendered {
+        Ok(svg) => svg,
+        Err(ServiceError::TooManyRequests { retry_after }) => {
+            return rate_limited_response(&request, retry_after);
         }
+        Err(err) => return error_response(err, page_lang),
     };
 
-    let svg = Card::new(
-        titles,
-        ranks,
-        column,
-        row,
-        DEFAULT_PANEL_SIZE,
-        margin_width,
-        margin_height,
-        no_background,
-        no_frame,
-    )
-    .render(&user_info, theme);
+    state.svg_cache.insert(request_cache_key, svg.clone()).await;
+
+    svg_response(svg, &headers)
+}
+
+async fn fetch_user_info(
+    state: &AppState,
+    username: &str,
+    include_private: bool,
+) -> Result<Fetched<models::UserInfo>, ServiceError> {
+    let user_key_cache = format!("v2-{username}-private={include_private}");
+    if let Some(cached) = state.user_cache.get(&user_key_cache).await {
+        return Ok(cached);
+    }
+
+    check_username(state, username)?;
+    match state
+        .github
+        .request_user_info(username, include_private)
+        .await
+    {
+        Ok(user_info) => {
+            let user_info = Fetched::new(user_info);
+            state
+                .user_cache
+                .insert(user_key_cache, user_info.clone())
+                .await;
+            Ok(user_info)
+        }
+        Err(err) => {
+            error!("GitHub API error for username='{username}': {err}");
+            Err(err)
+        }
+    }
+}
+
+async fn fetch_team_info(
+    state: &AppState,
+    usernames: &[String],
+) -> Result<Fetched<models::UserInfo>, ServiceError> {
+    let mut tasks = JoinSet::new();
+    for username in usernames {
+        let state = state.clone();
+        let username = username.clone();
+        tasks.spawn(async move {
+            let include_private =
+                should_include_private(state.default_username.as_deref(), &username);
+            fetch_user_info(&state, &username, include_private).await
+        });
+    }
 
-    let svg_bytes = Bytes::from(svg);
+    let mut members = Vec::with_capacity(usernames.len());
+    while let Some(result) = tasks.join_next().await {
+        let member = result.map_err(|err| {
+            error!("team member task failed: {err}");
+            ServiceError::Internal
+        })??;
+        members.push(member);
+    }
+
+    Ok(Fetched {
+        data: Arc::new(models::UserInfo::aggregate(
+            members.iter().map(|member| member.data.as_ref()),
+        )),
+        fetched_at: members
+            .iter()
+            .map(|member| member.fetched_at)
+            .max()
+            .unwrap_or_else(Utc::now),
+    })
+}
+
+async fn fetch_organization_info(
+    state: &AppState,
+    login: &str,
+) -> Result<Fetched<models::OrganizationInfo>, ServiceError> {
+    let organization_key_cache = format!("v1-org-{}", login.to_ascii_lowercase());
+    if let Some(cached) = state.organization_cache.get(&organization_key_cache).await {
+        return Ok(cached);
+    }
+
+    check_username(state, login)?;
+    match state.github.request_organization_info(login).await {
+        Ok(organization_info) => {
+            let organization_info = Fetched::new(organization_info);
+            state
+                .organization_cache
+                .insert(organization_key_cache, organization_info.clone())
+                .await;
+            Ok(organization_info)
+        }
+        Err(err) => {
+            error!("GitHub API error for organization='{login}': {err}");
+            Err(err)
+        }
+    }
+}
+
+async fn fetch_repository_info(
+    state: &AppState,
+    owner: &str,
+    name: &str,
+) -> Result<Fetched<models::RepositoryInfo>, ServiceError> {
+    let repository_key_cache = format!(
+        "v1-repo-{}/{}",
+        owner.to_ascii_lowercase(),
+        name.to_ascii_lowercase()
+    );
+    if let Some(cached) = state.repository_cache.get(&repository_key_cache).await {
+        return Ok(cached);
+    }
+
+    check_username(state, &format!("{owner}/{name}"))?;
+    match state.github.request_repository_info(owner, name).await {
+        Ok(repository_info) => {
+            let repository_info = Fetched::new(repository_info);
+            state
+                .repository_cache
+                .insert(repository_key_cache, repository_info.clone())
+                .await;
+            Ok(repository_info)
+        }
+        Err(err) => {
+            error!("GitHub API error for repository='{owner}/{name}': {err}");
+            Err(err)
+        }
+    }
+}
+
+// Counts a lookup that will reach GitHub against the distinct-username budget.
+fn check_username(state: &AppState, username: &str) -> Result<(), ServiceError> {
     state
-        .svg_cache
-        .insert(request_cache_key, svg_bytes.clone())
-        .await;
+        .rate_limiter
+        .check_username(username, Instant::now())
+        .map_err(|wait| {
+            warn!("username budget exhausted, rejecting '{username}'");
+            ServiceError::TooManyRequests {
+                retry_after: retry_after_secs(wait),
+            }
+        })
+}
 
-    svg_response(svg_bytes)
+// Unlike the card endpoint, the preview never guesses: any invalid parameter is reported.
+async fn preview_handler(
+    RawQuery(raw_query): RawQuery,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+) -> Response {
+    let params = ParsedParams::from_raw(raw_query.as_deref());
+    let page_lang = page_lang(&params, &headers);
+    let request = CardRequest::parse(&params);
+    if !request.errors.is_empty() {
+        return error_response(ServiceError::InvalidParameters(request.errors), page_lang);
+    }
+
+    let card_path = uri.path().strip_suffix("preview").unwrap_or("/");
+    let card_url = match raw_query {
+        Some(query) if !query.is_empty() => format!("{card_path}?{query}"),
+        _ => card_path.to_string(),
+    };
+    let body = html::preview_page(&card_url, page_lang.lang);
+    html_response(StatusCode::OK, body, page_lang)
 }
 
 async fn health_handler() -> impl IntoResponse {
     "ok"
 }
 
-fn cache_key(path: &str, raw_query: Option<&str>) -> String {
-    let query = raw_query.unwrap_or_default();
-    format!("v1:{path}?{query}")
+fn cache_key(path: &str, canonical_query: &str) -> String {
+    format!("v2:{path}?{canonical_query}")
+}
+
+fn canonical_query(target: &TrophyTarget, request: &CardRequest) -> String {
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(target.canonical_params())
+        .extend_pairs(request.canonical_params())
+        .finish();
+
+    // Commas and slashes are safe in query values and keep canonical URLs readable.
+    query.replace("%2C", ",").replace("%2F", "/")
 }
 
 fn resolve_username(
@@ -234,6 +687,49 @@ fn resolve_username(
     requested_username.or_else(|| default_username.map(str::to_string))
 }
 
+fn split_usernames(raw: &str) -> Vec<String> {
+    let mut usernames: Vec<String> = Vec::new();
+    for username in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        if !usernames
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(username))
+        {
+            usernames.push(username.to_string());
+        }
+    }
+    usernames
+}
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Option<T> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn parse_teams(raw: &str) -> HashMap<String, Vec<String>> {
+    raw.split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, members)| (name.trim().to_ascii_lowercase(), split_usernames(members)))
+        .filter(|(name, members)| !name.is_empty() && !members.is_empty())
+        .collect()
+}
+
+fn parse_repository(repo: &str) -> Option<(String, String)> {
+    let (owner, name) = repo.trim().split_once('/')?;
+    let is_valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+
+    if is_valid_part(owner) && is_valid_part(name) {
+        Some((owner.to_string(), name.to_string()))
+    } else {
+        None
+    }
+}
+
 fn should_include_private(default_username: Option<&str>, requested_username: &str) -> bool {
     default_username
         .map(|username| username.eq_ignore_ascii_case(requested_username))
@@ -246,9 +742,24 @@ fn cache_control_header() -> String {
     )
 }
 
-fn svg_response(svg: Bytes) -> Response {
-    let mut response = Response::new(Body::from(svg));
-    *response.status_mut() = StatusCode::OK;
+fn svg_response(svg: CachedSvg, request_headers: &HeaderMap) -> Response {
+    let (encoding, body) = svg.variant(Encoding::negotiate(
+        request_headers
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok()),
+    ));
+    let etag = svg.variant_etag(encoding);
+    let not_modified = is_not_modified(request_headers, &etag, svg.last_modified);
+    let mut response = if not_modified {
+        Response::new(Body::empty())
+    } else {
+        Response::new(Body::from(body))
+    };
+    *response.status_mut() = if not_modified {
+        StatusCode::NOT_MODIFIED
+    } else {
+        StatusCode::OK
+    };
 
     let cache_control = cache_control_header();
     let headers = response.headers_mut();
@@ -259,20 +770,120 @@ fn svg_response(svg: Bytes) -> Response {
     if let Ok(value) = HeaderValue::from_str(&cache_control) {
         headers.insert(header::CACHE_CONTROL, value);
     }
+    headers.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    if let Some(coding) = encoding.content_encoding() {
+        headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static(coding));
+    }
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&http_date(svg.last_modified)) {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
 
     response
 }
 
-fn html_response(status_code: StatusCode, body: String) -> Response {
+// FNV-1a over the body, so every instance behind a CDN agrees on the tag.
+fn etag(body: &[u8]) -> String {
+    let hash = body.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("\"{:x}-{hash:016x}\"", body.len())
+}
+
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+// `If-None-Match` takes precedence; `If-Modified-Since` is only consulted without it.
+fn is_not_modified(headers: &HeaderMap, etag: &str, last_modified: DateTime<Utc>) -> bool {
+    if let Some(if_none_match) = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+    {
+        return if_none_match.split(',').map(str::trim).any(|candidate| {
+            candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+        });
+    }
+
+    headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| last_modified.timestamp() <= since.timestamp())
+}
+
+fn redirect_response(location: &str) -> Response {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = StatusCode::MOVED_PERMANENTLY;
+
+    let cache_control = cache_control_header();
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(location) {
+        headers.insert(header::LOCATION, value);
+    }
+    if let Ok(value) = HeaderValue::from_str(&cache_control) {
+        headers.insert(header::CACHE_CONTROL, value);
+    }
+
+    response
+}
+
+fn rate_limited_response(request: &CardRequest, retry_after: u64) -> Response {
+    let svg = request
+        .card()
+        .render_rate_limited(request.theme, retry_after);
+    let mut response = Response::new(Body::from(svg));
+    *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("image/svg+xml"),
+    );
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+
+    response
+}
+
+fn page_lang(params: &ParsedParams, headers: &HeaderMap) -> PageLang {
+    match params
+        .get_optional_string("lang")
+        .and_then(|code| Lang::from_code(&code))
+    {
+        Some(lang) => PageLang {
+            lang,
+            negotiated: false,
+        },
+        None => PageLang {
+            lang: headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(Lang::from_accept_language)
+                .unwrap_or_default(),
+            negotiated: true,
+        },
+    }
+}
+
+fn html_response(status_code: StatusCode, body: String, page_lang: PageLang) -> Response {
     let mut response = Response::new(Body::from(body));
     *response.status_mut() = status_code;
 
-    let cache_control = cache_control_header();
     let headers = response.headers_mut();
     headers.insert(
         header::CONTENT_TYPE,
         HeaderValue::from_static("text/html; charset=utf-8"),
     );
+    // A page localized from `Accept-Language` must not be shared with other visitors.
+    let cache_control = if page_lang.negotiated {
+        headers.insert(header::VARY, HeaderValue::from_static("Accept-Language"));
+        format!("private, max-age={CACHE_MAX_AGE}")
+    } else {
+        cache_control_header()
+    };
     if let Ok(value) = HeaderValue::from_str(&cache_control) {
         headers.insert(header::CACHE_CONTROL, value);
     }
@@ -280,10 +891,22 @@ fn html_response(status_code: StatusCode, body: String) -> Response {
     response
 }
 
-fn error_response(error: ServiceError) -> Response {
-    let body = html::error_page(&error);
+fn error_response(error: ServiceError, page_lang: PageLang) -> Response {
+    let body = html::error_page(&error, page_lang.lang);
     let status = StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::BAD_REQUEST);
-    html_response(status, body)
+    let mut response = html_response(status, body, page_lang);
+    let headers = response.headers_mut();
+    if let ServiceError::TooManyRequests { retry_after } = error {
+        headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    }
+    // Reports echo the request's own typos, so they are never worth caching.
+    if matches!(
+        error,
+        ServiceError::InvalidParameters(_) | ServiceError::TooManyRequests { .. }
+    ) {
+        headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    }
+    response
 }
 
 async fn shutdown_signal() {
@@ -312,7 +935,17 @@ async fn shutdown_signal() {
 
 #[cfg(test)]
 mod tests {
-    use super::{resolve_username, should_include_private};
+    use super::{
+        CachedSvg, PageLang, TrophyTarget, canonical_query, error_response, html_response,
+        http_date, parse_repository, parse_teams, preview_handler, resolve_username,
+        should_include_private, split_usernames, svg_response,
+    };
+    use crate::{error::ServiceError, i18n::Lang, params::ParsedParams, request::CardRequest};
+    use axum::{
+        extract::{OriginalUri, RawQuery},
+        http::{HeaderMap, HeaderValue, StatusCode, header},
+    };
+    use chrono::{TimeZone, Utc};
 
     #[test]
     fn resolve_username_prefers_request_param() {
@@ -341,4 +974,209 @@ mod tests {
     fn should_not_include_private_when_usernames_differ() {
         assert!(!should_include_private(Some("alice"), "bob"));
     }
+
+    #[test]
+    fn parse_repository_splits_owner_and_name() {
+        assert_eq!(
+            parse_repository("h-sumiya/github-profile-trophy-rs"),
+            Some((
+                "h-sumiya".to_string(),
+                "github-profile-trophy-rs".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_repository_rejects_malformed_input() {
+        assert_eq!(parse_repository("h-sumiya"), None);
+        assert_eq!(parse_repository("h-sumiya/"), None);
+        assert_eq!(parse_repository("a/b/c"), None);
+    }
+
+    #[test]
+    fn split_usernames_dedupes_case_insensitively() {
+        assert_eq!(
+            split_usernames("alice, bob,,Alice,carol"),
+            vec!["alice", "bob", "carol"]
+        );
+    }
+
+    #[test]
+    fn parse_teams_reads_named_member_lists() {
+        let teams = parse_teams("Core=alice,bob; infra = carol ;broken;empty=");
+        assert_eq!(teams.len(), 2);
+        assert_eq!(teams["core"], vec!["alice", "bob"]);
+        assert_eq!(teams["infra"], vec!["carol"]);
+    }
+
+    #[test]
+    fn canonical_query_ignores_parameter_order_and_username_case() {
+        let query = |raw: &str, username: &str| {
+            let request = CardRequest::parse(&ParsedParams::from_raw(Some(raw)));
+            let target = TrophyTarget::User {
+                username: username.to_string(),
+                include_private: false,
+            };
+            canonical_query(&target, &request)
+        };
+
+        let canonical = query("theme=nord&title=Stars,Commits&unit=%E2%98%85", "Alice");
+        assert_eq!(
+            canonical,
+            "username=alice&theme=nord&title=Commits,Stars&unit=%E2%98%85"
+        );
+        assert_eq!(
+            query(
+                "unit=%E2%98%85&title=Commits&title=Stars&theme=nord",
+                "alice"
+            ),
+            canonical
+        );
+        assert_eq!(query(&canonical, "alice"), canonical);
+    }
+
+    #[test]
+    fn conditional_requests_match_etag_or_modification_time() {
+        let fetched_at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let svg = CachedSvg::new("<svg/>".to_string(), fetched_at);
+        assert_eq!(http_date(fetched_at), "Wed, 01 May 2024 12:00:00 GMT");
+
+        let status = |name, value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, HeaderValue::from_str(value).unwrap());
+            svg_response(svg.clone(), &headers).status()
+        };
+
+        assert_eq!(
+            status(header::IF_NONE_MATCH, &svg.etag),
+            StatusCode::NOT_MODIFIED
+        );
+        assert_eq!(
+            status(header::IF_NONE_MATCH, &format!("\"other\", W/{}", svg.etag)),
+            StatusCode::NOT_MODIFIED
+        );
+        assert_eq!(status(header::IF_NONE_MATCH, "\"other\""), StatusCode::OK);
+        assert_eq!(
+            status(header::IF_MODIFIED_SINCE, "Wed, 01 May 2024 12:00:00 GMT"),
+            StatusCode::NOT_MODIFIED
+        );
+        assert_eq!(
+            status(header::IF_MODIFIED_SINCE, "Wed, 01 May 2024 11:59:59 GMT"),
+            StatusCode::OK
+        );
+
+        let response = svg_response(svg.clone(), &HeaderMap::new());
+        assert_eq!(response.headers()[header::ETAG], svg.etag.as_str());
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            "Wed, 01 May 2024 12:00:00 GMT"
+        );
+        assert_ne!(
+            svg.etag,
+            CachedSvg::new("<svg><g/></svg>".to_string(), fetched_at).etag
+        );
+    }
+
+    #[test]
+    fn svg_is_minified_and_served_compressed_when_accepted() {
+        let body = format!("<svg>\n{}</svg>", "  <g fill=\"#fff\" />\n".repeat(200));
+        let svg = CachedSvg::new(body, Utc::now());
+        assert!(svg.body.starts_with(b"<svg><g fill=\"#fff\"/><g"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_ENCODING,
+            HeaderValue::from_static("gzip, br"),
+        );
+        let response = svg_response(svg.clone(), &headers);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+        assert_eq!(response.headers()[header::VARY], "Accept-Encoding");
+        let etag = response.headers()[header::ETAG]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(etag.ends_with("-br\""));
+        assert_ne!(etag, svg.etag);
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(&etag).unwrap());
+        assert_eq!(
+            svg_response(svg.clone(), &headers).status(),
+            StatusCode::NOT_MODIFIED
+        );
+
+        let response = svg_response(svg, &HeaderMap::new());
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[test]
+    fn validation_errors_are_not_cached() {
+        let request =
+            CardRequest::parse(&ParsedParams::from_raw(Some("theme=drakula&strict=true")));
+        let response = error_response(
+            ServiceError::InvalidParameters(request.errors),
+            PageLang::default(),
+        );
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+
+        let response = error_response(ServiceError::NotFound, PageLang::default());
+        assert!(
+            response.headers()[header::CACHE_CONTROL]
+                .to_str()
+                .unwrap()
+                .starts_with("public")
+        );
+    }
+
+    #[tokio::test]
+    async fn preview_reports_errors_instead_of_guessing() {
+        let preview = |query: &str| {
+            preview_handler(
+                RawQuery(Some(query.to_string())),
+                OriginalUri(format!("/preview?{query}").parse().unwrap()),
+                HeaderMap::new(),
+            )
+        };
+
+        let response = preview("username=alice&rank=Z").await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+
+        let response = preview("username=alice&theme=nord").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("<img src=\"/?username=alice&amp;theme=nord\""));
+    }
+
+    #[test]
+    fn only_explicitly_localized_pages_are_shared() {
+        let explicit = PageLang {
+            lang: Lang::Ja,
+            negotiated: false,
+        };
+        let response = html_response(StatusCode::OK, String::new(), explicit);
+        assert!(response.headers().get(header::VARY).is_none());
+        assert!(
+            response.headers()[header::CACHE_CONTROL]
+                .to_str()
+                .unwrap()
+                .starts_with("public")
+        );
+
+        let negotiated = PageLang {
+            lang: Lang::Ja,
+            negotiated: true,
+        };
+        let response = html_response(StatusCode::OK, String::new(), negotiated);
+        assert_eq!(response.headers()[header::VARY], "Accept-Language");
+        assert!(
+            response.headers()[header::CACHE_CONTROL]
+                .to_str()
+                .unwrap()
+                .starts_with("private")
+        );
+    }
 }
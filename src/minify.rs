@@ -0,0 +1,152 @@
+// A minifier for the SVG this crate generates: it strips comments and `<metadata>`, collapses
+// whitespace and merges every `<style>` into one. It is not a general XML processor.
+pub fn minify_svg(svg: &str) -> String {
+    merge_styles(&collapse_whitespace(svg))
+}
+
+fn collapse_whitespace(svg: &str) -> String {
+    let mut output = String::with_capacity(svg.len());
+    let mut rest = svg;
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, after)| after);
+        } else if rest.starts_with("<metadata") {
+            rest = rest
+                .split_once("</metadata>")
+                .map_or("", |(_, after)| after);
+        } else if rest.starts_with('<') {
+            let end = tag_end(rest);
+            push_tag(&mut output, &rest[..end]);
+            rest = &rest[end..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            push_text(&mut output, &rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+
+    output
+}
+
+// Index just past the `>` closing the tag at the start of `rest`, skipping quoted values.
+fn tag_end(rest: &str) -> usize {
+    let mut quote = None;
+    for (index, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return index + 1,
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+fn push_tag(output: &mut String, tag: &str) {
+    let mut quote = None;
+    let mut pending_space = false;
+
+    for c in tag.chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        let needed = quote.is_some() || !(matches!(c, '>' | '/' | '=') || output.ends_with('='));
+        if pending_space && needed {
+            output.push(' ');
+        }
+        pending_space = false;
+
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            _ => {}
+        }
+        output.push(c);
+    }
+}
+
+fn push_text(output: &mut String, text: &str) {
+    if text.trim().is_empty() {
+        return;
+    }
+
+    let mut pending_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+        } else {
+            if pending_space {
+                output.push(' ');
+                pending_space = false;
+            }
+            output.push(c);
+        }
+    }
+    if pending_space {
+        output.push(' ');
+    }
+}
+
+fn merge_styles(svg: &str) -> String {
+    const OPEN: &str = "<style>";
+    const CLOSE: &str = "</style>";
+
+    let mut rules: Vec<&str> = Vec::new();
+    let mut output = String::with_capacity(svg.len());
+    let mut insert_at = None;
+    let mut rest = svg;
+
+    while let Some(start) = rest.find(OPEN) {
+        let Some(length) = rest[start..].find(CLOSE) else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        insert_at.get_or_insert(output.len());
+
+        let content = &rest[start + OPEN.len()..start + length];
+        if !rules.contains(&content) {
+            rules.push(content);
+        }
+        rest = &rest[start + length + CLOSE.len()..];
+    }
+    output.push_str(rest);
+
+    if let Some(index) = insert_at {
+        output.insert_str(index, &format!("{OPEN}{}{CLOSE}", rules.concat()));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::minify_svg;
+
+    #[test]
+    fn collapses_whitespace_outside_values_and_text() {
+        let svg = "<svg  width=\"10\"\n  height=\"10\" >\n  <!-- note -->\n  <metadata>\nCreated by potrace\n</metadata>\n  <text  x=\"1\">Deep   Committer</text>\n  <path d=\"M0 0\n  L1 1\" />\n</svg>";
+
+        assert_eq!(
+            minify_svg(svg),
+            "<svg width=\"10\" height=\"10\"><text x=\"1\">Deep Committer</text><path d=\"M0 0 L1 1\"/></svg>"
+        );
+    }
+
+    #[test]
+    fn merges_and_dedupes_style_blocks() {
+        let svg = "<svg><title>a</title><style>.a{}</style><g><style>.b{}</style></g><style>.a{}</style></svg>";
+
+        assert_eq!(
+            minify_svg(svg),
+            "<svg><title>a</title><style>.a{}.b{}</style><g></g></svg>"
+        );
+    }
+
+    #[test]
+    fn rendered_cards_stay_intact() {
+        let svg = "<svg role=\"img\" aria-labelledby=\"a b\"><text>&lt;x&gt; y</text></svg>";
+        assert_eq!(minify_svg(svg), svg);
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::HashSet;
 
-use chrono::{Datelike, TimeZone, Utc};
+use chrono::{DateTime, Datelike, Months, TimeZone, Utc};
 use serde::Deserialize;
 
+use crate::i18n::Lang;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserActivity {
@@ -33,6 +35,19 @@ pub struct UserPullRequest {
     pub pull_requests: TotalCount,
 }
 
+// Every count is optional: a token without the scope for one of them, or a field GitHub
+// leaves null, only zeroes that count.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserCommunity {
+    pub gists: Option<TotalCount>,
+    pub repository_discussions: Option<TotalCount>,
+    pub repository_discussion_comments: Option<TotalCount>,
+    pub sponsors: Option<TotalCount>,
+    pub sponsoring: Option<TotalCount>,
+    pub packages: Option<TotalCount>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UserRepository {
     pub repositories: Repositories,
@@ -42,14 +57,25 @@ pub struct UserRepository {
 #[serde(rename_all = "camelCase")]
 pub struct Repositories {
     pub total_count: i64,
+    #[serde(default)]
+    pub page_info: PageInfo,
     pub nodes: Vec<Option<RepositoryNode>>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RepositoryNode {
     pub languages: Languages,
     pub stargazers: TotalCount,
+    #[serde(default)]
+    pub fork_count: i64,
     pub created_at: String,
 }
 
@@ -69,7 +95,65 @@ pub struct TotalCount {
     pub total_count: i64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountAge {
+    pub years: i64,
+    pub months: i64,
+    pub days: i64,
+}
+
+impl AccountAge {
+    pub fn between(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        if end <= start {
+            return Self::default();
+        }
+
+        let mut months = i64::from(end.year() - start.year()) * 12 + i64::from(end.month())
+            - i64::from(start.month());
+        let reached = |months: i64| {
+            u32::try_from(months)
+                .ok()
+                .and_then(|months| start.checked_add_months(Months::new(months)))
+                .is_some_and(|anniversary| anniversary <= end)
+        };
+        if months > 0 && !reached(months) {
+            months -= 1;
+        }
+
+        Self {
+            years: months.max(0) / 12,
+            months: months.max(0),
+            days: (end - start).num_days(),
+        }
+    }
+
+    fn since_millis(start_ts: i64, now: DateTime<Utc>) -> Self {
+        Utc.timestamp_millis_opt(start_ts)
+            .single()
+            .map(|start| Self::between(start, now))
+            .unwrap_or_default()
+    }
+}
+
+pub fn format_months(total_months: i64, lang: Lang) -> String {
+    let total_months = total_months.max(0);
+    match (total_months / 12, total_months % 12) {
+        (0, months) => lang.format("{months}m", &[("months", &months)]),
+        (years, 0) => lang.format("{years}y", &[("years", &years)]),
+        (years, months) => lang.format(
+            "{years}y {months}m",
+            &[("years", &years), ("months", &months)],
+        ),
+    }
+}
+
+pub trait MetricSource {
+    const METRICS: &'static [&'static str];
+
+    fn metric(&self, name: &str) -> Option<i64>;
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct UserInfo {
     pub total_commits: i64,
     pub total_followers: i64,
@@ -79,9 +163,16 @@ pub struct UserInfo {
     pub total_reviews: i64,
     pub total_stargazers: i64,
     pub total_repositories: i64,
+    pub total_forks: i64,
+    pub total_gists: i64,
+    pub total_discussions: i64,
+    pub total_answers: i64,
+    pub total_sponsors: i64,
+    pub total_sponsoring: i64,
+    pub total_packages: i64,
     pub language_count: i64,
-    pub duration_year: i64,
-    pub duration_days: i64,
+    pub languages: Vec<String>,
+    pub account_age: AccountAge,
     pub ancient_account: i64,
     pub joined_2020: i64,
     pub og_account: i64,
@@ -93,6 +184,7 @@ impl UserInfo {
         user_issue: UserIssue,
         user_pull_request: UserPullRequest,
         user_repository: UserRepository,
+        user_community: UserCommunity,
     ) -> Self {
         let total_commits = user_activity
             .contributions_collection
@@ -102,6 +194,7 @@ impl UserInfo {
                 .total_commit_contributions;
 
         let mut total_stargazers = 0i64;
+        let mut total_forks = 0i64;
         let mut languages: HashSet<String> = HashSet::new();
 
         let mut earliest_repo_date = user_activity.created_at.clone();
@@ -109,6 +202,7 @@ impl UserInfo {
 
         for repo in user_repository.repositories.nodes.iter().flatten() {
             total_stargazers += repo.stargazers.total_count;
+            total_forks += repo.fork_count;
 
             for lang in repo.languages.nodes.iter().flatten() {
                 languages.insert(lang.name.clone());
@@ -122,16 +216,11 @@ impl UserInfo {
             }
         }
 
-        let now_ts = Utc::now().timestamp_millis();
-        let duration_time = (now_ts - earliest_ts).max(0);
-        let duration_year = Utc
-            .timestamp_millis_opt(duration_time)
-            .single()
-            .map(|dt| i64::from(dt.year() - 1970))
-            .unwrap_or(0);
-        let duration_days = duration_time / (1000 * 60 * 60 * 24) / 100;
+        let account_age = AccountAge::since_millis(earliest_ts, Utc::now());
 
         let earliest_year = parse_rfc3339_to_year(&earliest_repo_date).unwrap_or(1970);
+        let mut languages = languages.into_iter().collect::<Vec<_>>();
+        languages.sort();
 
         Self {
             total_commits,
@@ -144,14 +233,268 @@ impl UserInfo {
                 .total_pull_request_review_contributions,
             total_stargazers,
             total_repositories: user_repository.repositories.total_count,
+            total_forks,
+            total_gists: count(&user_community.gists),
+            total_discussions: count(&user_community.repository_discussions),
+            total_answers: count(&user_community.repository_discussion_comments),
+            total_sponsors: count(&user_community.sponsors),
+            total_sponsoring: count(&user_community.sponsoring),
+            total_packages: count(&user_community.packages),
             language_count: languages.len() as i64,
-            duration_year,
-            duration_days,
+            languages,
+            account_age,
             ancient_account: i64::from(earliest_year <= 2010),
             joined_2020: i64::from(earliest_year == 2020),
             og_account: i64::from(earliest_year <= 2008),
         }
     }
+
+    pub fn aggregate<'a>(members: impl IntoIterator<Item = &'a UserInfo>) -> Self {
+        let mut team = Self::default();
+        let mut languages: HashSet<String> = HashSet::new();
+
+        for member in members {
+            team.total_commits += member.total_commits;
+            team.total_followers += member.total_followers;
+            team.total_issues += member.total_issues;
+            team.total_pull_requests += member.total_pull_requests;
+            team.total_reviews += member.total_reviews;
+            team.total_stargazers += member.total_stargazers;
+            team.total_repositories += member.total_repositories;
+            team.total_forks += member.total_forks;
+            team.total_gists += member.total_gists;
+            team.total_discussions += member.total_discussions;
+            team.total_answers += member.total_answers;
+            team.total_sponsors += member.total_sponsors;
+            team.total_sponsoring += member.total_sponsoring;
+            team.total_packages += member.total_packages;
+            team.total_organizations = team.total_organizations.max(member.total_organizations);
+            if member.account_age.days > team.account_age.days {
+                team.account_age = member.account_age;
+            }
+            team.ancient_account = team.ancient_account.max(member.ancient_account);
+            team.joined_2020 = team.joined_2020.max(member.joined_2020);
+            team.og_account = team.og_account.max(member.og_account);
+            languages.extend(member.languages.iter().cloned());
+        }
+
+        team.languages = languages.into_iter().collect();
+        team.languages.sort();
+        team.language_count = team.languages.len() as i64;
+
+        team
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationActivity {
+    pub created_at: String,
+    pub members_with_role: TotalCount,
+    pub sponsors: TotalCount,
+    pub repositories: Repositories,
+}
+
+#[derive(Debug, Clone)]
+pub struct OrganizationInfo {
+    pub total_members: i64,
+    pub total_repositories: i64,
+    pub total_stargazers: i64,
+    pub total_sponsors: i64,
+    pub language_count: i64,
+    pub account_age: AccountAge,
+}
+
+impl OrganizationInfo {
+    pub fn from_activity(organization: OrganizationActivity) -> Self {
+        let mut total_stargazers = 0i64;
+        let mut languages: HashSet<String> = HashSet::new();
+
+        for repo in organization.repositories.nodes.iter().flatten() {
+            total_stargazers += repo.stargazers.total_count;
+
+            for lang in repo.languages.nodes.iter().flatten() {
+                languages.insert(lang.name.clone());
+            }
+        }
+
+        let created_ts = parse_rfc3339_to_millis(&organization.created_at).unwrap_or(0);
+        let account_age = AccountAge::since_millis(created_ts, Utc::now());
+
+        Self {
+            total_members: organization.members_with_role.total_count,
+            total_repositories: organization.repositories.total_count,
+            total_stargazers,
+            total_sponsors: organization.sponsors.total_count,
+            language_count: languages.len() as i64,
+            account_age,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryActivity {
+    pub created_at: String,
+    pub stargazer_count: i64,
+    pub fork_count: i64,
+    pub watchers: TotalCount,
+    pub mentionable_users: TotalCount,
+    pub releases: TotalCount,
+    pub merged_pull_requests: TotalCount,
+    pub closed_issues: TotalCount,
+    pub languages: TotalCount,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepositoryInfo {
+    pub total_stargazers: i64,
+    pub total_forks: i64,
+    pub total_watchers: i64,
+    pub total_contributors: i64,
+    pub total_releases: i64,
+    pub total_merged_pull_requests: i64,
+    pub total_closed_issues: i64,
+    pub language_count: i64,
+    pub account_age: AccountAge,
+}
+
+impl RepositoryInfo {
+    pub fn from_activity(repository: RepositoryActivity) -> Self {
+        let created_ts = parse_rfc3339_to_millis(&repository.created_at).unwrap_or(0);
+        let account_age = AccountAge::since_millis(created_ts, Utc::now());
+
+        Self {
+            total_stargazers: repository.stargazer_count,
+            total_forks: repository.fork_count,
+            total_watchers: repository.watchers.total_count,
+            // GraphQL exposes no contributor count; mentionable users is the closest proxy.
+            total_contributors: repository.mentionable_users.total_count,
+            total_releases: repository.releases.total_count,
+            total_merged_pull_requests: repository.merged_pull_requests.total_count,
+            total_closed_issues: repository.closed_issues.total_count,
+            language_count: repository.languages.total_count,
+            account_age,
+        }
+    }
+}
+
+impl MetricSource for UserInfo {
+    const METRICS: &'static [&'static str] = &[
+        "total_commits",
+        "total_followers",
+        "total_issues",
+        "total_organizations",
+        "total_pull_requests",
+        "total_reviews",
+        "total_stargazers",
+        "total_repositories",
+        "total_forks",
+        "total_gists",
+        "total_discussions",
+        "total_answers",
+        "total_sponsors",
+        "total_sponsoring",
+        "total_packages",
+        "language_count",
+        "account_age_years",
+        "account_age_months",
+        "account_age_days",
+        "ancient_account",
+        "joined_2020",
+        "og_account",
+    ];
+
+    fn metric(&self, name: &str) -> Option<i64> {
+        let value = match name {
+            "total_commits" => self.total_commits,
+            "total_followers" => self.total_followers,
+            "total_issues" => self.total_issues,
+            "total_organizations" => self.total_organizations,
+            "total_pull_requests" => self.total_pull_requests,
+            "total_reviews" => self.total_reviews,
+            "total_stargazers" => self.total_stargazers,
+            "total_repositories" => self.total_repositories,
+            "total_forks" => self.total_forks,
+            "total_gists" => self.total_gists,
+            "total_discussions" => self.total_discussions,
+            "total_answers" => self.total_answers,
+            "total_sponsors" => self.total_sponsors,
+            "total_sponsoring" => self.total_sponsoring,
+            "total_packages" => self.total_packages,
+            "language_count" => self.language_count,
+            "account_age_years" => self.account_age.years,
+            "account_age_months" => self.account_age.months,
+            "account_age_days" => self.account_age.days,
+            "ancient_account" => self.ancient_account,
+            "joined_2020" => self.joined_2020,
+            "og_account" => self.og_account,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl MetricSource for OrganizationInfo {
+    const METRICS: &'static [&'static str] = &[
+        "total_members",
+        "total_repositories",
+        "total_stargazers",
+        "total_sponsors",
+        "language_count",
+        "account_age_years",
+        "account_age_months",
+        "account_age_days",
+    ];
+
+    fn metric(&self, name: &str) -> Option<i64> {
+        let value = match name {
+            "total_members" => self.total_members,
+            "total_repositories" => self.total_repositories,
+            "total_stargazers" => self.total_stargazers,
+            "total_sponsors" => self.total_sponsors,
+            "language_count" => self.language_count,
+            "account_age_years" => self.account_age.years,
+            "account_age_months" => self.account_age.months,
+            "account_age_days" => self.account_age.days,
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl MetricSource for RepositoryInfo {
+    const METRICS: &'static [&'static str] = &[
+        "total_stargazers",
+        "total_forks",
+        "total_watchers",
+        "total_contributors",
+        "total_releases",
+        "total_merged_pull_requests",
+        "total_closed_issues",
+        "language_count",
+        "account_age_years",
+        "account_age_months",
+        "account_age_days",
+    ];
+
+    fn metric(&self, name: &str) -> Option<i64> {
+        let value = match name {
+            "total_stargazers" => self.total_stargazers,
+            "total_forks" => self.total_forks,
+            "total_watchers" => self.total_watchers,
+            "total_contributors" => self.total_contributors,
+            "total_releases" => self.total_releases,
+            "total_merged_pull_requests" => self.total_merged_pull_requests,
+            "total_closed_issues" => self.total_closed_issues,
+            "language_count" => self.language_count,
+            "account_age_years" => self.account_age.years,
+            "account_age_months" => self.account_age.months,
+            "account_age_days" => self.account_age.days,
+            _ => return None,
+        };
+        Some(value)
+    }
 }
 
 fn parse_rfc3339_to_millis(input: &str) -> Option<i64> {
@@ -165,3 +508,98 @@ fn parse_rfc3339_to_year(input: &str) -> Option<i32> {
         .ok()
         .map(|dt| dt.year())
 }
+
+fn count(total: &Option<TotalCount>) -> i64 {
+    total.as_ref().map_or(0, |total| total.total_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{AccountAge, UserCommunity, UserInfo, format_months};
+    use crate::i18n::Lang;
+
+    #[test]
+    fn aggregate_sums_counts_and_unions_languages() {
+        let mut alice = UserInfo {
+            total_commits: 120,
+            total_organizations: 2,
+            languages: vec!["Rust".to_string(), "Go".to_string()],
+            ..Default::default()
+        };
+        alice.account_age.days = 1_200;
+
+        let mut bob = UserInfo {
+            total_commits: 80,
+            total_organizations: 5,
+            languages: vec!["Rust".to_string(), "TypeScript".to_string()],
+            ..Default::default()
+        };
+        bob.account_age.days = 3_400;
+
+        let team = UserInfo::aggregate([&alice, &bob]);
+        assert_eq!(team.total_commits, 200);
+        assert_eq!(team.total_organizations, 5);
+        assert_eq!(team.languages, vec!["Go", "Rust", "TypeScript"]);
+        assert_eq!(team.language_count, 3);
+        assert_eq!(team.account_age.days, 3_400);
+    }
+
+    #[test]
+    fn account_age_uses_calendar_months() {
+        let start = Utc.with_ymd_and_hms(2016, 1, 31, 12, 0, 0).unwrap();
+
+        let age = AccountAge::between(start, Utc.with_ymd_and_hms(2016, 2, 29, 12, 0, 0).unwrap());
+        assert_eq!((age.years, age.months, age.days), (0, 1, 29));
+
+        let age = AccountAge::between(start, Utc.with_ymd_and_hms(2023, 5, 1, 0, 0, 0).unwrap());
+        assert_eq!((age.years, age.months), (7, 87));
+        assert_eq!(format_months(age.months, Lang::En), "7y 3m");
+
+        let age = AccountAge::between(start, Utc.with_ymd_and_hms(2020, 1, 31, 12, 0, 0).unwrap());
+        assert_eq!(format_months(age.months, Lang::En), "4y");
+    }
+
+    #[test]
+    fn missing_community_counts_still_build_a_user() {
+        let parse = |json: &str| serde_json::from_str::<UserCommunity>(json).unwrap();
+        let user_info = |community| {
+            UserInfo::from_parts(
+                serde_json::from_str(
+                    r#"{
+                        "createdAt": "2015-06-01T00:00:00Z",
+                        "contributionsCollection": {
+                            "totalCommitContributions": 10,
+                            "restrictedContributionsCount": 0,
+                            "totalPullRequestReviewContributions": 0
+                        },
+                        "organizations": { "totalCount": 0 },
+                        "followers": { "totalCount": 3 }
+                    }"#,
+                )
+                .unwrap(),
+                serde_json::from_str(
+                    r#"{ "openIssues": { "totalCount": 0 }, "closedIssues": { "totalCount": 0 } }"#,
+                )
+                .unwrap(),
+                serde_json::from_str(r#"{ "pullRequests": { "totalCount": 0 } }"#).unwrap(),
+                serde_json::from_str(r#"{ "repositories": { "totalCount": 0, "nodes": [] } }"#)
+                    .unwrap(),
+                community,
+            )
+        };
+
+        let partial = user_info(parse(
+            r#"{ "gists": { "totalCount": 4 }, "sponsors": null, "packages": null }"#,
+        ));
+        assert_eq!(partial.total_gists, 4);
+        assert_eq!(partial.total_sponsors, 0);
+        assert_eq!(partial.total_packages, 0);
+        assert_eq!(partial.total_followers, 3);
+
+        let missing = user_info(UserCommunity::default());
+        assert_eq!(missing.total_commits, 10);
+        assert_eq!(missing.total_sponsoring, 0);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    constants::{DEFAULT_SCORE_UNIT, MAX_SCORE_UNIT_LENGTH},
+    i18n::Lang,
+};
+
+const COMPACT_SUFFIXES: [(u64, &str); 3] = [(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "k")];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Notation {
+    #[default]
+    Compact,
+    Exact,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    notation: Notation,
+    unit: Option<String>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            notation: Notation::Compact,
+            unit: Some(DEFAULT_SCORE_UNIT.to_string()),
+        }
+    }
+}
+
+impl NumberFormat {
+    pub fn from_params(notation: Option<&str>, unit: Option<&str>) -> Self {
+        let notation = match notation.map(str::to_ascii_lowercase).as_deref() {
+            Some("exact") => Notation::Exact,
+            _ => Notation::Compact,
+        };
+
+        let unit = match unit.map(str::trim) {
+            None => Some(DEFAULT_SCORE_UNIT.to_string()),
+            Some("") | Some("none") => None,
+            Some(unit)
+                if unit.chars().count() <= MAX_SCORE_UNIT_LENGTH
+                    && !unit
+                        .chars()
+                        .any(|c| c.is_control() || matches!(c, '<' | '>' | '&' | '"' | '\'')) =>
+            {
+                Some(unit.to_string())
+            }
+            Some(_) => Some(DEFAULT_SCORE_UNIT.to_string()),
+        };
+
+        Self { notation, unit }
+    }
+
+    // The `number` and `unit` values that differ from the defaults.
+    pub fn canonical(&self) -> (Option<&'static str>, Option<&str>) {
+        let notation = (self.notation == Notation::Exact).then_some("exact");
+        let unit = match self.unit.as_deref() {
+            Some(DEFAULT_SCORE_UNIT) => None,
+            Some(unit) => Some(unit),
+            None => Some("none"),
+        };
+
+        (notation, unit)
+    }
+
+    pub fn format(&self, value: i64, lang: Lang) -> String {
+        let (decimal_separator, group_separator) = separators(lang);
+        let sign = if value < 0 { "-" } else { "" };
+        let abs = value.unsigned_abs();
+
+        let number = match self.notation {
+            Notation::Exact => group_digits(abs, group_separator),
+            Notation::Compact => compact(abs, decimal_separator),
+        };
+        // Only the default unit is localized; a `unit=` value is the caller's own text.
+        let unit = match self.unit.as_deref() {
+            Some(DEFAULT_SCORE_UNIT) => lang.translate(DEFAULT_SCORE_UNIT),
+            Some(unit) => unit,
+            None => "",
+        };
+
+        format!("{sign}{number}{unit}")
+    }
+}
+
+fn compact(abs: u64, decimal_separator: char) -> String {
+    let Some(index) = COMPACT_SUFFIXES
+        .iter()
+        .position(|(divisor, _)| abs >= *divisor)
+    else {
+        return abs.to_string();
+    };
+
+    let (divisor, suffix) = COMPACT_SUFFIXES[index];
+    let tenths = (abs as f64 / divisor as f64 * 10.0).round() as u64;
+    // Promote 999_999 to "1.0M" rather than rendering "1000.0k".
+    let (tenths, suffix) = match index.checked_sub(1) {
+        Some(larger) if tenths >= 10_000 => {
+            let (larger_divisor, larger_suffix) = COMPACT_SUFFIXES[larger];
+            (
+                (abs as f64 / larger_divisor as f64 * 10.0).round() as u64,
+                larger_suffix,
+            )
+        }
+        _ => (tenths, suffix),
+    };
+
+    format!("{}{decimal_separator}{}{suffix}", tenths / 10, tenths % 10)
+}
+
+fn group_digits(abs: u64, group_separator: char) -> String {
+    let digits = abs.to_string();
+    let mut output = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            output.push(group_separator);
+        }
+        output.push(digit);
+    }
+
+    output
+}
+
+fn separators(lang: Lang) -> (char, char) {
+    match lang {
+        Lang::Es => (',', '.'),
+        Lang::En | Lang::Ja | Lang::Ko | Lang::Zh => ('.', ','),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumberFormat;
+    use crate::i18n::Lang;
+
+    #[test]
+    fn compact_format_matches_legacy_output() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(0, Lang::En), "0pt");
+        assert_eq!(format.format(5, Lang::En), "5pt");
+        assert_eq!(format.format(1000, Lang::En), "1.0kpt");
+        assert_eq!(format.format(-1200, Lang::En), "-1.2kpt");
+    }
+
+    #[test]
+    fn compact_format_uses_larger_suffixes() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(2_500_000, Lang::En), "2.5Mpt");
+        assert_eq!(format.format(999_999, Lang::En), "1.0Mpt");
+        assert_eq!(format.format(3_100_000_000, Lang::En), "3.1Bpt");
+        assert_eq!(format.format(2_500_000, Lang::Es), "2,5Mpt");
+        assert_eq!(format.format(1000, Lang::Ko), "1.0k점");
+    }
+
+    #[test]
+    fn exact_format_groups_digits_and_respects_unit() {
+        let exact = NumberFormat::from_params(Some("exact"), None);
+        assert_eq!(exact.format(2_500_000, Lang::En), "2,500,000pt");
+        assert_eq!(exact.format(2_500_000, Lang::Es), "2.500.000pt");
+        assert_eq!(exact.format(999, Lang::En), "999pt");
+
+        let bare = NumberFormat::from_params(Some("exact"), Some("none"));
+        assert_eq!(bare.format(12_345, Lang::En), "12,345");
+
+        let stars = NumberFormat::from_params(None, Some("★"));
+        assert_eq!(stars.format(1500, Lang::En), "1.5k★");
+
+        let catalog_key = NumberFormat::from_params(None, Some("Stars"));
+        assert_eq!(catalog_key.format(5, Lang::Ja), "5Stars");
+
+        let unsafe_unit = NumberFormat::from_params(None, Some("<b>"));
+        assert_eq!(unsafe_unit.format(5, Lang::En), "5pt");
+    }
+}
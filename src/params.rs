@@ -35,22 +35,6 @@ impl ParsedParams {
         self.values.get(key).and_then(|list| list.first()).cloned()
     }
 
-    pub fn get_number_value(&self, key: &str, default_value: i32) -> i32 {
-        self.values
-            .get(key)
-            .and_then(|list| list.first())
-            .and_then(|value| value.parse::<i32>().ok())
-            .unwrap_or(default_value)
-    }
-
-    pub fn get_boolean_value(&self, key: &str, default_value: bool) -> bool {
-        self.values
-            .get(key)
-            .and_then(|list| list.first())
-            .map(|value| value == "true")
-            .unwrap_or(default_value)
-    }
-
     pub fn get_all(&self, key: &str) -> Vec<String> {
         self.values.get(key).cloned().unwrap_or_default()
     }
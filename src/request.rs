@@ -0,0 +1,657 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use crate::{
+    constants::{
+        DEFAULT_MARGIN_H, DEFAULT_MARGIN_W, DEFAULT_MAX_COLUMN, DEFAULT_MAX_ROW,
+        DEFAULT_NO_ANIMATION, DEFAULT_NO_BACKGROUND, DEFAULT_NO_FRAME, DEFAULT_PANEL_SIZE,
+        DEFAULT_SCALE, EASY_SCALE, HARD_SCALE, MAX_PANEL_SIZE, MAX_SCALE, MIN_PANEL_SIZE,
+        MIN_SCALE,
+    },
+    definitions::definitions,
+    font,
+    i18n::Lang,
+    number::NumberFormat,
+    params::ParsedParams,
+    svg::{Animation, Card, IconSet, Layout},
+    themes::{THEME_NAMES, Theme, resolve_theme},
+    trophy::{RankFilter, SortOrder},
+};
+
+const UNBOUNDED: RangeInclusive<i32> = i32::MIN..=i32::MAX;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    UnknownTheme {
+        value: String,
+        suggestion: Option<&'static str>,
+    },
+    UnknownTitle {
+        value: String,
+        suggestion: Option<&'static str>,
+    },
+    OutOfRange {
+        name: &'static str,
+        value: i32,
+        range: RangeInclusive<i32>,
+    },
+    InvalidNumber {
+        name: &'static str,
+        value: String,
+    },
+    InvalidBoolean {
+        name: &'static str,
+        value: String,
+    },
+    InvalidRank {
+        value: String,
+    },
+    InvalidScale {
+        value: String,
+    },
+}
+
+impl ParamError {
+    pub fn describe(&self, lang: Lang) -> String {
+        let (message, suggestion) = match self {
+            Self::UnknownTheme { value, suggestion } => (
+                lang.format("Unknown theme '{value}'.", &[("value", value)]),
+                *suggestion,
+            ),
+            Self::UnknownTitle { value, suggestion } => (
+                lang.format("Unknown trophy title '{value}'.", &[("value", value)]),
+                *suggestion,
+            ),
+            Self::OutOfRange { name, value, range } if *range.end() == i32::MAX => (
+                lang.format(
+                    "{name} must be at least {min}, got {value}.",
+                    &[("name", name), ("min", range.start()), ("value", value)],
+                ),
+                None,
+            ),
+            Self::OutOfRange { name, value, range } => (
+                lang.format(
+                    "{name} must be between {min} and {max}, got {value}.",
+                    &[
+                        ("name", name),
+                        ("min", range.start()),
+                        ("max", range.end()),
+                        ("value", value),
+                    ],
+                ),
+                None,
+            ),
+            Self::InvalidNumber { name, value } => (
+                lang.format(
+                    "{name} must be a whole number, got '{value}'.",
+                    &[("name", name), ("value", value)],
+                ),
+                None,
+            ),
+            Self::InvalidBoolean { name, value } => (
+                lang.format(
+                    "{name} must be true or false, got '{value}'.",
+                    &[("name", name), ("value", value)],
+                ),
+                None,
+            ),
+            Self::InvalidRank { value } => (
+                lang.format(
+                    "'{value}' is not a valid rank filter. Use ranks such as SSS, AA or B, ranges such as >=A or B..SS, and a leading - to exclude.",
+                    &[("value", value)],
+                ),
+                None,
+            ),
+            Self::InvalidScale { value } => (
+                lang.format(
+                    "scale must be a positive number, got '{value}'.",
+                    &[("value", value)],
+                ),
+                None,
+            ),
+        };
+
+        match suggestion {
+            Some(suggestion) => format!(
+                "{message} {}",
+                lang.format(
+                    "Did you mean '{suggestion}'?",
+                    &[("suggestion", &suggestion)]
+                )
+            ),
+            None => message,
+        }
+    }
+}
+
+// Card options parsed from the query. Invalid values fall back to the same defaults as before
+// and are recorded in `errors`, which the handler reports for `strict=true`.
+#[derive(Debug, Clone)]
+pub struct CardRequest {
+    pub lang: Option<Lang>,
+    pub theme: &'static Theme,
+    theme_name: &'static str,
+    pub strict: bool,
+    pub errors: Vec<ParamError>,
+    titles: Vec<String>,
+    ranks: RankFilter,
+    column: i32,
+    row: i32,
+    panel_size: i32,
+    target_width: Option<i32>,
+    margin_width: i32,
+    margin_height: i32,
+    no_background: bool,
+    no_frame: bool,
+    animation: Animation,
+    icons: IconSet,
+    embed_font: bool,
+    scale: f64,
+    number_format: NumberFormat,
+    layout: Layout,
+    sort: SortOrder,
+    limit: Option<usize>,
+}
+
+impl CardRequest {
+    pub fn parse(params: &ParsedParams) -> Self {
+        let mut reader = Reader {
+            params,
+            errors: Vec::new(),
+        };
+
+        let theme_name = params.get_string_value("theme", "default");
+        if theme_name != "default" && !THEME_NAMES.contains(&theme_name.as_str()) {
+            let suggestion = suggest(&theme_name, THEME_NAMES.into_iter().chain(["default"]));
+            reader.errors.push(ParamError::UnknownTheme {
+                value: theme_name.clone(),
+                suggestion,
+            });
+        }
+
+        let titles = params.get_all_csv("title");
+        reader.check_titles(&titles);
+        let ranks = RankFilter::parse(
+            &params.get_all_csv("rank"),
+            params.get_optional_string("min-rank").as_deref(),
+        )
+        .unwrap_or_else(|error| {
+            reader.errors.push(error);
+            RankFilter::default()
+        });
+        let scale = reader.scale();
+
+        let row = reader
+            .number("row", 1..=i32::MAX)
+            .unwrap_or(DEFAULT_MAX_ROW)
+            .max(1);
+        let column = match reader.number("column", UNBOUNDED) {
+            Some(column) if column == -1 || column >= 1 => column,
+            Some(column) => {
+                reader.errors.push(ParamError::OutOfRange {
+                    name: "column",
+                    value: column,
+                    range: 1..=i32::MAX,
+                });
+                DEFAULT_MAX_COLUMN
+            }
+            None => DEFAULT_MAX_COLUMN,
+        };
+        let panel_size = reader
+            .number("size", MIN_PANEL_SIZE..=MAX_PANEL_SIZE)
+            .unwrap_or(DEFAULT_PANEL_SIZE)
+            .clamp(MIN_PANEL_SIZE, MAX_PANEL_SIZE);
+        let target_width = reader
+            .number("width", 1..=i32::MAX)
+            .filter(|width| *width > 0);
+        let margin_width = reader
+            .number("margin-w", UNBOUNDED)
+            .unwrap_or(DEFAULT_MARGIN_W);
+        let margin_height = reader
+            .number("margin-h", UNBOUNDED)
+            .unwrap_or(DEFAULT_MARGIN_H);
+        let limit = reader
+            .number("limit", 1..=i32::MAX)
+            .filter(|limit| *limit > 0)
+            .map(|limit| limit as usize);
+
+        let no_background = reader.boolean("no-bg", DEFAULT_NO_BACKGROUND);
+        let no_frame = reader.boolean("no-frame", DEFAULT_NO_FRAME);
+        let animation = Animation::from_param(
+            params.get_optional_string("animation").as_deref(),
+            reader.boolean("no-animation", DEFAULT_NO_ANIMATION),
+        );
+        let strict = reader.boolean("strict", false);
+
+        let lang = params
+            .get_optional_string("lang")
+            .and_then(|code| Lang::from_code(&code));
+
+        Self {
+            lang,
+            theme: resolve_theme(&theme_name),
+            theme_name: THEME_NAMES
+                .into_iter()
+                .find(|name| *name == theme_name)
+                .unwrap_or("default"),
+            strict,
+            errors: reader.errors,
+            titles,
+            ranks,
+            column,
+            row,
+            panel_size,
+            target_width,
+            margin_width,
+            margin_height,
+            no_background,
+            no_frame,
+            animation,
+            icons: IconSet::from_param(params.get_optional_string("icons").as_deref()),
+            embed_font: params
+                .get_optional_string("font")
+                .is_some_and(|value| value.eq_ignore_ascii_case("embed"))
+                && font::covers(lang.unwrap_or_default()),
+            scale,
+            number_format: NumberFormat::from_params(
+                params.get_optional_string("number").as_deref(),
+                params.get_optional_string("unit").as_deref(),
+            ),
+            layout: Layout::from_param(params.get_optional_string("layout").as_deref()),
+            sort: SortOrder::from_param(params.get_optional_string("sort").as_deref()),
+            limit,
+        }
+    }
+
+    // Options that differ from the defaults in a fixed order, so equivalent queries share one
+    // cache entry. Titles keep their order only when `sort=title` depends on it.
+    pub fn canonical_params(&self) -> Vec<(&'static str, String)> {
+        let mut seen = HashSet::new();
+        let mut titles = self
+            .titles
+            .iter()
+            .filter(|title| seen.insert(title.as_str()))
+            .cloned()
+            .collect::<Vec<_>>();
+        if self.sort != SortOrder::Title {
+            titles.sort();
+        }
+        let (ranks, min_rank) = self.ranks.canonical();
+        let (number, unit) = self.number_format.canonical();
+
+        [
+            ("lang", self.lang.map(|lang| lang.code().to_string())),
+            (
+                "theme",
+                (self.theme_name != "default").then(|| self.theme_name.to_string()),
+            ),
+            ("title", (!titles.is_empty()).then(|| titles.join(","))),
+            ("rank", ranks),
+            ("min-rank", min_rank.map(str::to_string)),
+            ("column", non_default(self.column, DEFAULT_MAX_COLUMN)),
+            ("row", non_default(self.row, DEFAULT_MAX_ROW)),
+            ("size", non_default(self.panel_size, DEFAULT_PANEL_SIZE)),
+            ("width", self.target_width.map(|width| width.to_string())),
+            ("margin-w", non_default(self.margin_width, DEFAULT_MARGIN_W)),
+            (
+                "margin-h",
+                non_default(self.margin_height, DEFAULT_MARGIN_H),
+            ),
+            (
+                "no-bg",
+                non_default(self.no_background, DEFAULT_NO_BACKGROUND),
+            ),
+            ("no-frame", non_default(self.no_frame, DEFAULT_NO_FRAME)),
+            (
+                "animation",
+                non_default(self.animation.as_param(), Animation::default().as_param()),
+            ),
+            (
+                "icons",
+                non_default(self.icons.as_param(), IconSet::default().as_param()),
+            ),
+            ("font", self.embed_font.then(|| "embed".to_string())),
+            ("scale", non_default(self.scale, DEFAULT_SCALE)),
+            ("number", number.map(str::to_string)),
+            ("unit", unit.map(str::to_string)),
+            (
+                "layout",
+                non_default(self.layout.as_param(), Layout::default().as_param()),
+            ),
+            (
+                "sort",
+                non_default(self.sort.as_param(), SortOrder::default().as_param()),
+            ),
+            ("limit", self.limit.map(|limit| limit.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+    }
+
+    pub fn card(&self) -> Card {
+        Card::new(
+            self.titles.clone(),
+            self.ranks.clone(),
+            self.column,
+            self.row,
+            self.panel_size,
+            self.margin_width,
+            self.margin_height,
+            self.no_background,
+            self.no_frame,
+        )
+        .with_scale(self.scale)
+        .with_lang(self.lang.unwrap_or_default())
+        .with_number_format(self.number_format.clone())
+        .with_animation(self.animation)
+        .with_icons(self.icons)
+        .with_font(self.embed_font.then(font::embedded))
+        .with_layout(self.layout)
+        .with_target_width(self.target_width)
+        .with_sort(self.sort, self.limit)
+    }
+}
+
+struct Reader<'a> {
+    params: &'a ParsedParams,
+    errors: Vec<ParamError>,
+}
+
+impl Reader<'_> {
+    // Returns the parsed value even when it is out of range so callers keep their fallback.
+    fn number(&mut self, name: &'static str, range: RangeInclusive<i32>) -> Option<i32> {
+        let value = self.params.get_optional_string(name)?;
+        match value.parse::<i32>() {
+            Ok(number) => {
+                if !range.contains(&number) {
+                    self.errors.push(ParamError::OutOfRange {
+                        name,
+                        value: number,
+                        range,
+                    });
+                }
+                Some(number)
+            }
+            Err(_) => {
+                self.errors.push(ParamError::InvalidNumber { name, value });
+                None
+            }
+        }
+    }
+
+    fn boolean(&mut self, name: &'static str, default_value: bool) -> bool {
+        match self.params.get_optional_string(name).as_deref() {
+            None => default_value,
+            Some("true") => true,
+            Some("false") => false,
+            Some(value) => {
+                self.errors.push(ParamError::InvalidBoolean {
+                    name,
+                    value: value.to_string(),
+                });
+                false
+            }
+        }
+    }
+
+    // A scale that is not a positive number falls back to `difficulty=`; positive values are
+    // clamped to the supported range.
+    fn scale(&mut self) -> f64 {
+        let scale = self.params.get_optional_string("scale");
+        if let Some(value) = &scale
+            && parse_scale(value).is_none()
+        {
+            self.errors.push(ParamError::InvalidScale {
+                value: value.clone(),
+            });
+        }
+
+        resolve_scale(
+            self.params.get_optional_string("difficulty").as_deref(),
+            scale.as_deref(),
+        )
+    }
+
+    // Titles match any alias, but suggestions only offer the canonical title.
+    fn check_titles(&mut self, titles: &[String]) {
+        let definitions = definitions();
+        let known = || {
+            [
+                &definitions.user,
+                &definitions.organization,
+                &definitions.repository,
+            ]
+            .into_iter()
+            .flatten()
+        };
+
+        for title in titles {
+            let title = title.strip_prefix('-').unwrap_or(title);
+            if !known().any(|definition| definition.filter_titles.contains(&title)) {
+                self.errors.push(ParamError::UnknownTitle {
+                    value: title.to_string(),
+                    suggestion: suggest(title, known().map(|definition| definition.title)),
+                });
+            }
+        }
+    }
+}
+
+fn non_default<T: PartialEq + ToString>(value: T, default_value: T) -> Option<String> {
+    (value != default_value).then(|| value.to_string())
+}
+
+pub fn resolve_scale(difficulty: Option<&str>, scale: Option<&str>) -> f64 {
+    if let Some(scale) = scale.and_then(parse_scale) {
+        return scale.clamp(MIN_SCALE, MAX_SCALE);
+    }
+
+    match difficulty.map(str::to_ascii_lowercase).as_deref() {
+        Some("easy") => EASY_SCALE,
+        Some("hard") => HARD_SCALE,
+        _ => DEFAULT_SCALE,
+    }
+}
+
+fn parse_scale(value: &str) -> Option<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|scale| scale.is_finite() && *scale > 0.0)
+}
+
+fn suggest<'a>(value: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let value = value.to_lowercase();
+    let max_distance = (value.chars().count() / 3).max(2);
+
+    candidates
+        .map(|candidate| (edit_distance(&value, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right.len()).collect::<Vec<_>>();
+
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current = vec![i + 1; right.len() + 1];
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous[j] + usize::from(left_char != *right_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CardRequest, ParamError, resolve_scale};
+    use crate::{i18n::Lang, params::ParsedParams};
+
+    fn parse(query: &str) -> CardRequest {
+        CardRequest::parse(&ParsedParams::from_raw(Some(query)))
+    }
+
+    #[test]
+    fn resolve_scale_prefers_numeric_scale_over_difficulty() {
+        assert_eq!(resolve_scale(Some("easy"), None), 0.5);
+        assert_eq!(resolve_scale(Some("HARD"), None), 2.0);
+        assert_eq!(resolve_scale(Some("easy"), Some("1.5")), 1.5);
+        assert_eq!(resolve_scale(None, Some("100")), 10.0);
+        assert_eq!(resolve_scale(Some("unknown"), Some("abc")), 1.0);
+        assert_eq!(resolve_scale(Some("easy"), Some("-2")), 0.5);
+        assert_eq!(resolve_scale(None, Some("0")), 1.0);
+        assert_eq!(resolve_scale(None, Some("NaN")), 1.0);
+        assert_eq!(resolve_scale(None, Some("inf")), 1.0);
+    }
+
+    #[test]
+    fn valid_requests_have_no_errors() {
+        let request = parse("theme=onedark&title=Stars,-Commits&column=-1&no-bg=true&size=220");
+        assert!(request.errors.is_empty());
+        assert!(!request.strict);
+    }
+
+    #[test]
+    fn invalid_values_are_collected_with_suggestions() {
+        let request = parse("theme=drakula&title=Starz&column=abc&no-bg=1&size=20&strict=true");
+        assert!(request.strict);
+        assert_eq!(
+            request.errors,
+            [
+                ParamError::UnknownTheme {
+                    value: "drakula".into(),
+                    suggestion: Some("dracula"),
+                },
+                ParamError::UnknownTitle {
+                    value: "Starz".into(),
+                    suggestion: Some("Stars"),
+                },
+                ParamError::InvalidNumber {
+                    name: "column",
+                    value: "abc".into(),
+                },
+                ParamError::OutOfRange {
+                    name: "size",
+                    value: 20,
+                    range: 55..=330,
+                },
+                ParamError::InvalidBoolean {
+                    name: "no-bg",
+                    value: "1".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            request.errors[0].describe(Lang::En),
+            "Unknown theme 'drakula'. Did you mean 'dracula'?"
+        );
+        assert_eq!(
+            request.errors[3].describe(Lang::En),
+            "size must be between 55 and 330, got 20."
+        );
+    }
+
+    #[test]
+    fn canonical_params_ignore_order_aliases_and_defaults() {
+        let canonical = |query| parse(query).canonical_params();
+
+        let params = canonical(
+            "title=Stars,Commits&no-animation=true&theme=nord&difficulty=easy&rank=>=A,-S&unit=pt",
+        );
+        assert_eq!(
+            params,
+            [
+                ("theme", "nord".to_string()),
+                ("title", "Commits,Stars".to_string()),
+                ("rank", "A..SECRET,-S".to_string()),
+                ("animation", "none".to_string()),
+                ("scale", "0.5".to_string()),
+            ]
+        );
+        assert_eq!(
+            params,
+            canonical("scale=0.5&rank=-S,A..SECRET&animation=none&title=Commits,Stars&theme=nord")
+        );
+        assert_eq!(
+            canonical("theme=default&column=8&sort=rank&layout=grid&icons=classic"),
+            []
+        );
+        assert_eq!(
+            canonical("title=Stars,Commits&sort=title"),
+            [
+                ("title", "Stars,Commits".to_string()),
+                ("sort", "title".to_string()),
+            ]
+        );
+        assert_eq!(
+            canonical("title=Stars,Commits,Stars&sort=title"),
+            canonical("title=Stars,Commits&sort=title")
+        );
+        assert_eq!(
+            canonical("title=Stars,Commits,Stars"),
+            canonical("title=Stars,Commits")
+        );
+    }
+
+    #[test]
+    fn distant_values_get_no_suggestion() {
+        let request = parse("theme=zzzzzzzz");
+        assert_eq!(
+            request.errors,
+            [ParamError::UnknownTheme {
+                value: "zzzzzzzz".into(),
+                suggestion: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_ranks_and_scales_fall_back_with_errors() {
+        let request = parse("rank=Z&min-rank=A&scale=-1&difficulty=hard");
+        assert_eq!(
+            request.errors,
+            [
+                ParamError::InvalidRank { value: "Z".into() },
+                ParamError::InvalidScale { value: "-1".into() },
+            ]
+        );
+        assert_eq!(request.canonical_params(), [("scale", "2".to_string())]);
+        assert!(
+            request.errors[0]
+                .describe(Lang::En)
+                .starts_with("'Z' is not a valid rank filter.")
+        );
+
+        let request = parse("min-rank=QQ&scale=inf");
+        assert_eq!(
+            request.errors,
+            [
+                ParamError::InvalidRank { value: "QQ".into() },
+                ParamError::InvalidScale {
+                    value: "inf".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn font_embedding_is_limited_to_latin_cards() {
+        let font = |query| {
+            parse(query)
+                .canonical_params()
+                .into_iter()
+                .any(|param| param == ("font", "embed".to_string()))
+        };
+
+        assert!(font("font=embed"));
+        assert!(font("font=EMBED&lang=es"));
+        assert!(!font("font=embed&lang=ja"));
+        assert!(!font("font=embed&lang=zh"));
+    }
+}
@@ -1,18 +1,153 @@
-use std::fmt::Write;
+use std::{
+    borrow::Cow,
+    fmt::{Display, Write},
+};
 
 use crate::{
-    constants::DEFAULT_PANEL_SIZE,
-    models::UserInfo,
+    constants::{DEFAULT_PANEL_SIZE, DEFAULT_SCALE, EASY_SCALE, HARD_SCALE},
+    font::Font,
+    i18n::Lang,
+    models::{OrganizationInfo, RepositoryInfo, UserInfo},
+    number::NumberFormat,
     themes::Theme,
-    trophy::{Rank, Trophy, TrophyList},
+    trophy::{Rank, RankFilter, SortOrder, Trophy, TrophyList, rank_order_index},
 };
 
 const LEAF_ICON_TEMPLATE: &str = include_str!("leaf_icon.template.svg");
+const LAUREL_SYMBOL: &str = "laurel";
+const TROPHY_SYMBOL: &str = "trophy";
+const MEDAL_GLYPH: &str = "medal";
+const GLYPH_TEMPLATES: [(&str, &str); 17] = [
+    ("clock", include_str!("icons/clock.template.svg")),
+    ("code", include_str!("icons/code.template.svg")),
+    ("comment", include_str!("icons/comment.template.svg")),
+    ("commit", include_str!("icons/commit.template.svg")),
+    ("crown", include_str!("icons/crown.template.svg")),
+    ("eye", include_str!("icons/eye.template.svg")),
+    ("fork", include_str!("icons/fork.template.svg")),
+    ("heart", include_str!("icons/heart.template.svg")),
+    ("issue", include_str!("icons/issue.template.svg")),
+    ("medal", include_str!("icons/medal.template.svg")),
+    (
+        "organization",
+        include_str!("icons/organization.template.svg"),
+    ),
+    ("package", include_str!("icons/package.template.svg")),
+    ("people", include_str!("icons/people.template.svg")),
+    (
+        "pull-request",
+        include_str!("icons/pull-request.template.svg"),
+    ),
+    ("repository", include_str!("icons/repository.template.svg")),
+    ("star", include_str!("icons/star.template.svg")),
+    ("tag", include_str!("icons/tag.template.svg")),
+];
+const DEFAULT_FRAME_COLOR: &str = "#e1e4e8";
+const COMPARISON_HEADER_HEIGHT: i32 = 24;
+const DIFFICULTY_LABEL_HEIGHT: i32 = 12;
+const FONT_FAMILY: &str = "Segoe UI,Helvetica,Arial,sans-serif,Apple Color Emoji,Segoe UI Emoji";
+const TROPHY_ICON_PATHS: &str = "<path d=\"M7 10h2v4H7v-4z\"/><path d=\"M10 11c0 .552-.895 1-2 1s-2-.448-2-1 .895-1 2-1 2 .448 2 1z\"/><path fill-rule=\"evenodd\" d=\"M12.5 3a2 2 0 1 0 0 4 2 2 0 0 0 0-4zm-3 2a3 3 0 1 1 6 0 3 3 0 0 1-6 0zm-6-2a2 2 0 1 0 0 4 2 2 0 0 0 0-4zm-3 2a3 3 0 1 1 6 0 3 3 0 0 1-6 0z\"/><path d=\"M3 1h10c-.495 3.467-.5 10-5 10S3.495 4.467 3 1zm0 15a1 1 0 0 1 1-1h8a1 1 0 0 1 1 1H3zm2-1a1 1 0 0 1 1-1h4a1 1 0 0 1 1 1H5z\"/>";
+
+const LIST_WIDTH: i32 = 320;
+const LIST_ROW_HEIGHT: i32 = 32;
+const LIST_ICON_SIZE: i32 = 28;
+const BADGE_HEIGHT: i32 = 20;
+const HERO_COLUMNS: i32 = 4;
+const FADE_STAGGER_MS: i32 = 80;
+const SHINE_WIDTH: i32 = 40;
+const PANEL_TEXT_PADDING: i32 = 5;
+const MIN_TEXT_SCALE: f64 = 0.75;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Layout {
+    #[default]
+    Grid,
+    List,
+    Badge,
+    Hero,
+}
+
+impl Layout {
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(str::to_ascii_lowercase).as_deref() {
+            Some("list") | Some("compact") => Self::List,
+            Some("badge") => Self::Badge,
+            Some("hero") => Self::Hero,
+            _ => Self::Grid,
+        }
+    }
+
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::Grid => "grid",
+            Self::List => "list",
+            Self::Badge => "badge",
+            Self::Hero => "hero",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Animation {
+    None,
+    #[default]
+    Bar,
+    Fade,
+    Shine,
+}
+
+impl Animation {
+    pub fn from_param(value: Option<&str>, no_animation: bool) -> Self {
+        match value.map(str::to_ascii_lowercase).as_deref() {
+            Some("none") | Some("static") => Self::None,
+            Some("bar") => Self::Bar,
+            Some("fade") => Self::Fade,
+            Some("shine") => Self::Shine,
+            _ if no_animation => Self::None,
+            _ => Self::Bar,
+        }
+    }
+
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Bar => "bar",
+            Self::Fade => "fade",
+            Self::Shine => "shine",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IconSet {
+    #[default]
+    Classic,
+    Category,
+    Minimal,
+}
+
+impl IconSet {
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(str::to_ascii_lowercase).as_deref() {
+            Some("category") | Some("categories") => Self::Category,
+            Some("minimal") => Self::Minimal,
+            _ => Self::Classic,
+        }
+    }
+
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Category => "category",
+            Self::Minimal => "minimal",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Card {
     titles: Vec<String>,
-    ranks: Vec<String>,
+    ranks: RankFilter,
     max_column: i32,
     max_row: i32,
     panel_size: i32,
@@ -20,13 +155,23 @@ pub struct Card {
     margin_height: i32,
     no_background: bool,
     no_frame: bool,
+    scale: f64,
+    lang: Lang,
+    number_format: NumberFormat,
+    animation: Animation,
+    icons: IconSet,
+    layout: Layout,
+    target_width: Option<i32>,
+    sort: SortOrder,
+    limit: Option<usize>,
+    font: Option<&'static Font>,
 }
 
 impl Card {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         titles: Vec<String>,
-        ranks: Vec<String>,
+        ranks: RankFilter,
         max_column: i32,
         max_row: i32,
         panel_size: i32,
@@ -45,37 +190,291 @@ impl Card {
             margin_height,
             no_background,
             no_frame,
+            scale: DEFAULT_SCALE,
+            lang: Lang::En,
+            number_format: NumberFormat::default(),
+            animation: Animation::Bar,
+            icons: IconSet::Classic,
+            layout: Layout::Grid,
+            target_width: None,
+            sort: SortOrder::Rank,
+            limit: None,
+            font: None,
         }
     }
 
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    pub fn with_number_format(mut self, number_format: NumberFormat) -> Self {
+        self.number_format = number_format;
+        self
+    }
+
+    pub fn with_animation(mut self, animation: Animation) -> Self {
+        self.animation = animation;
+        self
+    }
+
+    pub fn with_icons(mut self, icons: IconSet) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn with_sort(mut self, sort: SortOrder, limit: Option<usize>) -> Self {
+        self.sort = sort;
+        self.limit = limit;
+        self
+    }
+
+    pub fn with_font(mut self, font: Option<&'static Font>) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn with_target_width(mut self, target_width: Option<i32>) -> Self {
+        self.target_width = target_width;
+        self
+    }
+
     pub fn render(&self, user_info: &UserInfo, theme: &Theme) -> String {
-        let mut trophy_list = TrophyList::new(user_info);
+        self.render_trophy_list(TrophyList::new(user_info, self.scale), theme)
+    }
 
-        trophy_list.filter_by_hidden();
+    pub fn render_organization(
+        &self,
+        organization_info: &OrganizationInfo,
+        theme: &Theme,
+    ) -> String {
+        self.render_trophy_list(
+            TrophyList::new_organization(organization_info, self.scale),
+            theme,
+        )
+    }
 
-        if !self.titles.is_empty() {
-            let include_titles = self
-                .titles
-                .iter()
-                .filter(|title| !title.starts_with('-'))
-                .cloned()
-                .collect::<Vec<_>>();
+    pub fn render_repository(&self, repository_info: &RepositoryInfo, theme: &Theme) -> String {
+        self.render_trophy_list(
+            TrophyList::new_repository(repository_info, self.scale),
+            theme,
+        )
+    }
+
+    pub fn render_comparison(
+        &self,
+        left: (&str, &UserInfo),
+        right: (&str, &UserInfo),
+        theme: &Theme,
+    ) -> String {
+        let (left_name, left_info) = left;
+        let (right_name, right_info) = right;
+        let left_list = TrophyList::new(left_info, self.scale);
+        let right_list = TrophyList::new(right_info, self.scale);
+
+        let mut left_visible = left_list.clone();
+        self.apply_filters(&mut left_visible);
+        let mut right_visible = right_list.clone();
+        self.apply_filters(&mut right_visible);
+
+        let is_visible = |list: &TrophyList, title: &str| {
+            list.items().iter().any(|trophy| trophy.title == title)
+        };
+
+        let mut pairs = left_list
+            .items()
+            .iter()
+            .zip(right_list.items())
+            .filter(|(left, right)| {
+                is_visible(&left_visible, left.title) || is_visible(&right_visible, right.title)
+            })
+            .collect::<Vec<_>>();
+        pairs.sort_by_key(|(left, right)| {
+            rank_order_index(left.rank).min(rank_order_index(right.rank))
+        });
+
+        let ids = self.ids(
+            theme,
+            pairs.iter().flat_map(|(left, right)| [*left, *right]),
+            &format!("{left_name}|{right_name}"),
+        );
+
+        let right_x = self.panel_size + self.margin_width;
+        let width = self.panel_size * 2 + self.margin_width;
+        let row = (pairs.len() as i32).max(1);
+        let height =
+            COMPARISON_HEADER_HEIGHT + get_height(self.panel_size, self.margin_height, row);
 
-            if !include_titles.is_empty() {
-                trophy_list.filter_by_titles(&include_titles);
+        let mut body = String::with_capacity(pairs.len() * 5_000);
+        for (name, center) in [
+            (left_name, self.panel_size / 2),
+            (right_name, right_x + self.panel_size / 2),
+        ] {
+            body.push_str(&text_tag(center, 16, "middle", 13.0, theme.title).text(name));
+        }
+
+        for (index, (left, right)) in pairs.iter().enumerate() {
+            let y =
+                COMPARISON_HEADER_HEIGHT + (self.panel_size + self.margin_height) * index as i32;
+            let (left_leads, right_leads) =
+                (left.score() > right.score(), right.score() > left.score());
+
+            for (trophy, x, leads, id_prefix) in [
+                (left, 0, left_leads, "left-"),
+                (right, right_x, right_leads, "right-"),
+            ] {
+                let frame_color = if leads {
+                    theme.next_rank_bar
+                } else {
+                    DEFAULT_FRAME_COLOR
+                };
+                body.push_str(&self.render_trophy(
+                    trophy,
+                    theme,
+                    &ids,
+                    (x, y, self.panel_size),
+                    self.no_frame && !leads,
+                    frame_color,
+                    id_prefix,
+                ));
             }
+        }
+
+        let title = self.lang.format(
+            "{left} vs {right}",
+            &[("left", &left_name), ("right", &right_name)],
+        );
+        let description = pairs
+            .iter()
+            .flat_map(|(left, right)| {
+                [
+                    format!("{left_name} {}", self.trophy_label(left)),
+                    format!("{right_name} {}", self.trophy_label(right)),
+                ]
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
 
-            trophy_list.filter_by_exclusion_titles(&self.titles);
+        self.wrap_card(width, height, body, theme, &ids, &title, &description)
+    }
+
+    fn apply_filters(&self, trophy_list: &mut TrophyList) {
+        trophy_list.filter_by_hidden();
+
+        let include_titles = self
+            .titles
+            .iter()
+            .filter(|title| !title.starts_with('-'))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // Opt-in trophies are only ever drawn by name, so `filter_by_titles` keeps them
+        // exactly when asked for.
+        if include_titles.is_empty() {
+            trophy_list.filter_by_opt_in();
+        } else {
+            trophy_list.filter_by_titles(&include_titles);
         }
 
+        trophy_list.filter_by_exclusion_titles(&self.titles);
+
         if !self.ranks.is_empty() {
             trophy_list.filter_by_ranks(&self.ranks);
         }
+    }
+
+    // A card-shaped notice served with a 429 so embeds show why the trophies are missing.
+    pub fn render_rate_limited(&self, theme: &Theme, retry_after: u64) -> String {
+        let card = Self {
+            scale: DEFAULT_SCALE,
+            animation: Animation::None,
+            ..self.clone()
+        };
+        let width = DEFAULT_PANEL_SIZE * 3;
+        let height = DEFAULT_PANEL_SIZE;
+        let max_width = (width - PANEL_TEXT_PADDING * 4) as f64;
+        let title = self.lang.translate("Too Many Requests");
+        let message = self.lang.format(
+            "Please retry in {seconds} seconds.",
+            &[("seconds", &retry_after)],
+        );
+
+        let (title_text, title_size) = card.fit_text(title, 16.0, max_width);
+        let (message_text, message_size) = card.fit_text(&message, 11.0, max_width);
+        let body = [
+            card.panel_frame(width, height, theme, card.no_frame, DEFAULT_FRAME_COLOR),
+            text_tag(width / 2, 50, "middle", title_size, theme.title).text(&title_text),
+            text_tag(width / 2, 74, "middle", message_size, theme.text).text(&message_text),
+        ]
+        .concat();
+
+        let ids = card.ids(theme, [], "rate-limited");
+        card.wrap_card(width, height, body, theme, &ids, title, &message)
+    }
+
+    fn render_trophy_list(&self, mut trophy_list: TrophyList, theme: &Theme) -> String {
+        self.apply_filters(&mut trophy_list);
+        trophy_list.sort(self.sort, &self.titles);
+        if let Some(limit) = self.limit {
+            trophy_list.truncate(limit);
+        }
+
+        let trophies = trophy_list.items();
+        let ids = self.ids(theme, trophies, "");
+        let (width, height, body) = match self.layout {
+            Layout::Grid => self.render_grid(trophies, theme, &ids),
+            Layout::List => self.render_list(trophies, theme, &ids),
+            Layout::Badge => self.render_badge(trophies.first(), theme),
+            Layout::Hero => self.render_hero(trophies, theme, &ids),
+        };
+        // Describe only what is drawn: the badge shows one trophy and the hero grid may overflow.
+        let (drawn, hidden) = match self.layout {
+            Layout::Badge => (&trophies[..trophies.len().min(1)], 0),
+            Layout::Hero => {
+                let hidden = self.hero_hidden(trophies.len().saturating_sub(1));
+                (&trophies[..trophies.len() - hidden], hidden)
+            }
+            Layout::Grid | Layout::List => (trophies, 0),
+        };
+        let mut description = drawn
+            .iter()
+            .map(|trophy| self.trophy_label(trophy))
+            .collect::<Vec<_>>();
+        if hidden > 0 {
+            description.push(
+                self.lang
+                    .format("{count} more trophies", &[("count", &hidden)]),
+            );
+        }
+        let description = description.join("; ");
 
-        trophy_list.sort_by_rank();
+        self.wrap_card(
+            width,
+            height,
+            body,
+            theme,
+            &ids,
+            self.lang.translate("GitHub Profile Trophy"),
+            &description,
+        )
+    }
 
-        let mut max_column = if self.max_column == -1 {
-            (trophy_list.len() as i32).max(1)
+    fn render_grid(&self, trophies: &[Trophy], theme: &Theme, ids: &Ids) -> (i32, i32, String) {
+        let mut max_column = if let Some(target_width) = self.target_width {
+            ((target_width + self.margin_width) / (self.panel_size + self.margin_width))
+                .clamp(1, (trophies.len() as i32).max(1))
+        } else if self.max_column == -1 {
+            (trophies.len() as i32).max(1)
         } else if self.max_column <= 0 {
             1
         } else {
@@ -88,17 +487,462 @@ impl Card {
 
         let width = self.panel_size * max_column + self.margin_width * (max_column - 1);
 
-        let row = get_row(trophy_list.len(), max_column, self.max_row);
+        let row = get_row(trophies.len(), max_column, self.max_row);
         let height = get_height(self.panel_size, self.margin_height, row);
 
-        let body = self.render_trophies(trophy_list.items(), theme, max_column);
+        let body = self.render_trophies(trophies, theme, ids, max_column);
+        (width, height, body)
+    }
+
+    fn render_list(&self, trophies: &[Trophy], theme: &Theme, ids: &Ids) -> (i32, i32, String) {
+        let row = (trophies.len() as i32).max(1);
+        let (row_width, row_height) = (self.scaled(LIST_WIDTH), self.scaled(LIST_ROW_HEIGHT));
+        let height = get_height(row_height, self.margin_height, row);
+        let icon_offset = (LIST_ROW_HEIGHT - LIST_ICON_SIZE) / 2;
+        let text_y = LIST_ROW_HEIGHT / 2 + 4;
 
-        format!(
-            "\n    <svg\n      width=\"{width}\"\n      height=\"{height}\"\n      viewBox=\"0 0 {width} {height}\"\n      fill=\"none\"\n      xmlns=\"http://www.w3.org/2000/svg\"\n    >\n      {body}\n    </svg>"
-        )
+        let mut body = String::with_capacity(trophies.len() * 2_500);
+        for (index, trophy) in trophies.iter().enumerate() {
+            let panel_id = ids.id(trophy.title);
+            let title_id = format!("{panel_id}-title");
+            let summary = format!(
+                "{} · {}",
+                trophy.rank.as_str(),
+                trophy.bottom_message(self.lang, &self.number_format)
+            );
+
+            let children = [
+                Tag::new("title")
+                    .attr("id", &title_id)
+                    .text(&self.trophy_label(trophy)),
+                self.panel_frame(
+                    LIST_WIDTH,
+                    LIST_ROW_HEIGHT,
+                    theme,
+                    self.no_frame,
+                    DEFAULT_FRAME_COLOR,
+                ),
+                Tag::new("svg")
+                    .attr("x", icon_offset)
+                    .attr("y", icon_offset)
+                    .attr("width", LIST_ICON_SIZE)
+                    .attr("height", LIST_ICON_SIZE)
+                    .attr(
+                        "viewBox",
+                        format!("0 0 {DEFAULT_PANEL_SIZE} {DEFAULT_PANEL_SIZE}"),
+                    )
+                    .wrap(&get_trophy_icon(
+                        theme,
+                        trophy,
+                        self.icons,
+                        &format!("{panel_id}-gradient"),
+                        ids,
+                    )),
+                text_tag(
+                    LIST_ICON_SIZE + icon_offset * 2 + 4,
+                    text_y,
+                    "start",
+                    12.0,
+                    theme.title,
+                )
+                .text(self.lang.translate(trophy.title)),
+                text_tag(LIST_WIDTH / 2, text_y, "start", 10.5, theme.text)
+                    .text(self.lang.translate(trophy.top_message)),
+                text_tag(LIST_WIDTH - 8, text_y, "end", 10.0, theme.text).text(&summary),
+            ]
+            .concat();
+            let children = self.fade_in(ids, children, index as i32);
+
+            body.push_str(
+                &Tag::new("svg")
+                    .attr("x", 0)
+                    .attr("y", (row_height + self.margin_height) * index as i32)
+                    .attr("width", row_width)
+                    .attr("height", row_height)
+                    .attr("viewBox", format!("0 0 {LIST_WIDTH} {LIST_ROW_HEIGHT}"))
+                    .attr("role", "img")
+                    .attr("aria-labelledby", &title_id)
+                    .wrap(&children),
+            );
+        }
+
+        (row_width, height, body)
+    }
+
+    fn render_badge(&self, trophy: Option<&Trophy>, theme: &Theme) -> (i32, i32, String) {
+        let label = self.lang.translate("GitHub Profile Trophy");
+        let (label, value, (value_fill, value_text)) = match trophy {
+            Some(trophy) => {
+                let (base, text, _, _) = rank_palette(theme, trophy.rank);
+                (
+                    self.lang.translate(trophy.title),
+                    format!(
+                        "{} · {}",
+                        trophy.rank.as_str(),
+                        self.lang.translate(trophy.top_message)
+                    ),
+                    (base, text),
+                )
+            }
+            None => (
+                label,
+                self.lang.translate("Unknown").to_string(),
+                (theme.default_rank_base, theme.default_rank_text),
+            ),
+        };
+
+        let label_width = approximate_text_width(label, 11.0) + 12;
+        let value_width = approximate_text_width(&value, 11.0) + 12;
+        let width = label_width + value_width;
+        let background_opacity = if self.no_background { "0" } else { "1" };
+        let text_y = BADGE_HEIGHT / 2 + 4;
+
+        let body = [
+            Tag::new("rect")
+                .attr("width", label_width)
+                .attr("height", BADGE_HEIGHT)
+                .attr("fill", theme.background)
+                .attr("fill-opacity", background_opacity)
+                .empty(),
+            Tag::new("rect")
+                .attr("x", label_width)
+                .attr("width", value_width)
+                .attr("height", BADGE_HEIGHT)
+                .attr("fill", value_fill)
+                .attr("fill-opacity", background_opacity)
+                .empty(),
+            Tag::new("rect")
+                .attr("x", 0.5)
+                .attr("y", 0.5)
+                .attr("rx", 3)
+                .attr("width", width - 1)
+                .attr("height", BADGE_HEIGHT - 1)
+                .attr("fill", "none")
+                .attr("stroke", DEFAULT_FRAME_COLOR)
+                .attr("stroke-opacity", if self.no_frame { "0" } else { "1" })
+                .empty(),
+            text_tag(label_width / 2, text_y, "middle", 11.0, theme.title).text(label),
+            text_tag(
+                label_width + value_width / 2,
+                text_y,
+                "middle",
+                11.0,
+                value_text,
+            )
+            .text(&value),
+        ]
+        .concat();
+
+        let (scaled_width, scaled_height) = (self.scaled(width), self.scaled(BADGE_HEIGHT));
+        let body = Tag::new("svg")
+            .attr("width", scaled_width)
+            .attr("height", scaled_height)
+            .attr("viewBox", format!("0 0 {width} {BADGE_HEIGHT}"))
+            .wrap(&body);
+
+        (scaled_width, scaled_height, body)
+    }
+
+    fn render_hero(&self, trophies: &[Trophy], theme: &Theme, ids: &Ids) -> (i32, i32, String) {
+        let hero_size = self.panel_size * 2;
+        let Some((hero, rest)) = trophies.split_first() else {
+            return (hero_size, hero_size, String::new());
+        };
+
+        let mut body = self.render_trophy(
+            hero,
+            theme,
+            ids,
+            (0, 0, hero_size),
+            self.no_frame,
+            DEFAULT_FRAME_COLOR,
+            "",
+        );
+
+        let icon_size = self.panel_size / 2;
+        let slots = self.hero_slots();
+        let hidden = self.hero_hidden(rest.len());
+        let shown = &rest[..rest.len() - hidden];
+        let start_x = hero_size + self.margin_width;
+        let slot = |index: usize, title_id: &str, children: &str| {
+            Tag::new("svg")
+                .attr("x", start_x + icon_size * (index as i32 % HERO_COLUMNS))
+                .attr("y", icon_size * (index as i32 / HERO_COLUMNS))
+                .attr("width", icon_size)
+                .attr("height", icon_size)
+                .attr(
+                    "viewBox",
+                    format!("0 0 {DEFAULT_PANEL_SIZE} {DEFAULT_PANEL_SIZE}"),
+                )
+                .attr("role", "img")
+                .attr("aria-labelledby", title_id)
+                .wrap(children)
+        };
+        for (index, trophy) in shown.iter().enumerate() {
+            let panel_id = ids.id(&format!("icon-{}", trophy.title));
+            let title_id = format!("{panel_id}-title");
+
+            let children = [
+                Tag::new("title")
+                    .attr("id", &title_id)
+                    .text(&self.trophy_label(trophy)),
+                self.panel_frame(
+                    DEFAULT_PANEL_SIZE,
+                    DEFAULT_PANEL_SIZE,
+                    theme,
+                    self.no_frame,
+                    DEFAULT_FRAME_COLOR,
+                ),
+                get_trophy_icon(
+                    theme,
+                    trophy,
+                    self.icons,
+                    &format!("{panel_id}-gradient"),
+                    ids,
+                ),
+            ]
+            .concat();
+
+            body.push_str(&slot(index, &title_id, &children));
+        }
+        if hidden > 0 {
+            let title_id = ids.id("icon-more-title");
+            let children = [
+                Tag::new("title").attr("id", &title_id).text(
+                    &self
+                        .lang
+                        .format("{count} more trophies", &[("count", &hidden)]),
+                ),
+                self.panel_frame(
+                    DEFAULT_PANEL_SIZE,
+                    DEFAULT_PANEL_SIZE,
+                    theme,
+                    self.no_frame,
+                    DEFAULT_FRAME_COLOR,
+                ),
+                text_tag(DEFAULT_PANEL_SIZE / 2, 68, "middle", 36.0, theme.text)
+                    .text(&format!("+{hidden}")),
+            ]
+            .concat();
+            body.push_str(&slot(slots - 1, &title_id, &children));
+        }
+
+        let width = if rest.is_empty() {
+            hero_size
+        } else {
+            start_x + icon_size * HERO_COLUMNS.min(rest.len() as i32)
+        };
+        (width, hero_size, body)
+    }
+
+    fn hero_slots(&self) -> usize {
+        let rows = (self.panel_size * 2) / (self.panel_size / 2).max(1);
+        (HERO_COLUMNS * rows) as usize
     }
 
-    fn render_trophies(&self, trophies: &[Trophy], theme: &Theme, max_column: i32) -> String {
+    // When the hero's icon grid overflows, its last slot counts the trophies left out.
+    fn hero_hidden(&self, icons: usize) -> usize {
+        let slots = self.hero_slots();
+        match icons > slots {
+            true => icons - (slots - 1),
+            false => 0,
+        }
+    }
+
+    // Seeds the id namespace with everything that affects the markup, so two different
+    // cards inlined into one page never share an id while identical cards stay cacheable.
+    fn ids<'a>(
+        &self,
+        theme: &Theme,
+        trophies: impl IntoIterator<Item = &'a Trophy>,
+        extra: &str,
+    ) -> Ids {
+        let mut seed = format!(
+            "{theme:?}|{:?}|{}|{}|{:?}|{:?}|{:?}|{}|{}|{}|{}|{extra}",
+            self.layout,
+            self.lang.code(),
+            self.scale,
+            self.number_format,
+            self.animation,
+            self.icons,
+            self.font.is_some(),
+            self.no_background,
+            self.no_frame,
+            self.panel_size,
+        );
+        for trophy in trophies {
+            let _ = write!(seed, "|{}={}", trophy.title, trophy.score());
+        }
+
+        Ids::new(&seed)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn wrap_card(
+        &self,
+        width: i32,
+        height: i32,
+        mut body: String,
+        theme: &Theme,
+        ids: &Ids,
+        title: &str,
+        description: &str,
+    ) -> String {
+        let mut height = height;
+
+        if let Some(label) = difficulty_label(self.scale, self.lang) {
+            height += DIFFICULTY_LABEL_HEIGHT;
+            body.push_str(
+                &Tag::new("text")
+                    .attr("x", width - 2)
+                    .attr("y", height - 3)
+                    .attr("text-anchor", "end")
+                    .attr("font-family", "Segoe UI,Helvetica,Arial,sans-serif")
+                    .attr("font-size", 8)
+                    .attr("fill", theme.text)
+                    .attr("opacity", 0.6)
+                    .text(&label),
+            );
+        }
+
+        let title_id = ids.id("card-title");
+        let desc_id = ids.id("card-desc");
+        let header = [
+            Tag::new("title").attr("id", &title_id).text(title),
+            Tag::new("desc").attr("id", &desc_id).text(description),
+            self.stylesheet(ids),
+            self.font_face(ids),
+        ]
+        .concat();
+        let symbols = symbols(ids, theme, &body);
+
+        Tag::new("svg")
+            .attr("width", width)
+            .attr("height", height)
+            .attr("viewBox", format!("0 0 {width} {height}"))
+            .attr("fill", "none")
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .attr("role", "img")
+            .attr("aria-labelledby", format!("{title_id} {desc_id}"))
+            .wrap(&(header + &body + &symbols))
+    }
+
+    fn stylesheet(&self, ids: &Ids) -> String {
+        if self.animation == Animation::None {
+            return String::new();
+        }
+
+        let (bar, fade, shine) = (ids.id("bar"), ids.id("fade"), ids.id("shine"));
+        let mut style = format!(
+            ".{bar}{{transform-box:fill-box;transform-origin:left;animation:{bar} 1s ease-in-out both}}@keyframes {bar}{{from{{transform:scaleX(0)}}to{{transform:scaleX(1)}}}}"
+        );
+        let mut defs = String::new();
+        match self.animation {
+            Animation::Fade => {
+                let _ = write!(
+                    style,
+                    ".{fade}{{animation:{fade} .6s ease-in both}}@keyframes {fade}{{from{{opacity:0}}to{{opacity:1}}}}"
+                );
+            }
+            Animation::Shine => {
+                let _ = write!(
+                    style,
+                    ".{shine}{{animation:{shine} 3s ease-in-out infinite}}@keyframes {shine}{{from{{transform:translateX(0)}}60%,to{{transform:translateX({}px)}}}}",
+                    DEFAULT_PANEL_SIZE + SHINE_WIDTH * 2,
+                );
+                defs = Tag::new("defs").wrap(
+                    &Tag::new("linearGradient")
+                        .attr("id", ids.id("shine-gradient"))
+                        .wrap(
+                            &[("0%", 0.0), ("50%", 0.6), ("100%", 0.0)]
+                                .iter()
+                                .map(|(offset, opacity)| {
+                                    Tag::new("stop")
+                                        .attr("offset", offset)
+                                        .attr("stop-color", "#FFF")
+                                        .attr("stop-opacity", opacity)
+                                        .empty()
+                                })
+                                .collect::<String>(),
+                        ),
+                );
+            }
+            Animation::None | Animation::Bar => {}
+        }
+        let _ = write!(
+            style,
+            "@media (prefers-reduced-motion: reduce){{.{bar},.{fade},.{shine}{{animation:none}}}}"
+        );
+
+        Tag::new("style").wrap(&style) + &defs
+    }
+
+    // Embeds only the glyphs the card draws and points every sans-serif text at them; the
+    // Courier rank letters keep their own face.
+    fn font_face(&self, ids: &Ids) -> String {
+        let Some(font) = self.font else {
+            return String::new();
+        };
+
+        let family = ids.id("font");
+        Tag::new("style").wrap(&format!(
+            "@font-face{{font-family:{family};src:url({}) format(\"woff2\")}}text[font-family^=\"Segoe UI\"]{{font-family:{family},{FONT_FAMILY}}}",
+            font.data_uri(),
+        ))
+    }
+
+    fn text_width(&self, text: &str, font_size: f64) -> f64 {
+        self.font
+            .and_then(|font| font.text_width(text, font_size))
+            .unwrap_or_else(|| approximate_text_width(text, font_size) as f64)
+    }
+
+    // Shrinks text that would overflow `max_width`, down to `MIN_TEXT_SCALE` of its size, and
+    // truncates it with an ellipsis past that.
+    fn fit_text<'a>(&self, text: &'a str, font_size: f64, max_width: f64) -> (Cow<'a, str>, f64) {
+        let width = self.text_width(text, font_size);
+        if width <= max_width {
+            return (Cow::Borrowed(text), font_size);
+        }
+
+        let fitted = (font_size * max_width / width * 10.0).floor() / 10.0;
+        let min_size = font_size * MIN_TEXT_SCALE;
+        if fitted >= min_size {
+            return (Cow::Borrowed(text), fitted);
+        }
+
+        let mut truncated = text.to_string();
+        while truncated.pop().is_some() {
+            let candidate = format!("{}…", truncated.trim_end());
+            if self.text_width(&candidate, min_size) <= max_width {
+                return (Cow::Owned(candidate), min_size);
+            }
+        }
+        (Cow::Borrowed("…"), min_size)
+    }
+
+    // Staggers panels diagonally across the grid when the fade animation is selected.
+    fn fade_in(&self, ids: &Ids, content: String, order: i32) -> String {
+        if self.animation != Animation::Fade {
+            return content;
+        }
+
+        Tag::new("g")
+            .attr("class", ids.id("fade"))
+            .attr(
+                "style",
+                format!("animation-delay:{}ms", order * FADE_STAGGER_MS),
+            )
+            .wrap(&content)
+    }
+
+    fn render_trophies(
+        &self,
+        trophies: &[Trophy],
+        theme: &Theme,
+        ids: &Ids,
+        max_column: i32,
+    ) -> String {
         let mut output = String::with_capacity(trophies.len() * 2_500);
 
         for (index, trophy) in trophies.iter().enumerate() {
@@ -107,19 +951,263 @@ impl Card {
             let x = self.panel_size * current_column + self.margin_width * current_column;
             let y = self.panel_size * current_row + self.margin_height * current_row;
 
-            output.push_str(&render_trophy(
+            output.push_str(&self.render_trophy(
                 trophy,
                 theme,
-                x,
-                y,
-                self.panel_size,
-                self.no_background,
+                ids,
+                (x, y, self.panel_size),
                 self.no_frame,
+                DEFAULT_FRAME_COLOR,
+                "",
             ));
         }
 
         output
     }
+
+    #[allow(clippy::too_many_arguments)]
+    fn render_trophy(
+        &self,
+        trophy: &Trophy,
+        theme: &Theme,
+        ids: &Ids,
+        (x, y, size): (i32, i32, i32),
+        no_frame: bool,
+        frame_color: &str,
+        id_prefix: &str,
+    ) -> String {
+        let panel_id = format!("{id_prefix}{}", trophy.title);
+        // Inner geometry is laid out for the default panel and scaled by the outer size.
+        let panel_size = DEFAULT_PANEL_SIZE;
+        let title_id = ids.id(&format!("{panel_id}-title"));
+
+        let text = |y: i32, font_size: f64, fill: &str, content: &str| {
+            let max_width = (panel_size - PANEL_TEXT_PADDING * 2) as f64;
+            // Only `font=embed` cards are fitted, so existing embeds keep rendering as before.
+            let (content, font_size) = match self.font {
+                Some(_) => self.fit_text(content, font_size, max_width),
+                None => (Cow::Borrowed(content), font_size),
+            };
+            text_tag(panel_size / 2, y, "middle", font_size, fill).text(&content)
+        };
+
+        let children = [
+            Tag::new("title")
+                .attr("id", &title_id)
+                .text(&self.trophy_label(trophy)),
+            self.panel_frame(panel_size, panel_size, theme, no_frame, frame_color),
+            get_trophy_icon(
+                theme,
+                trophy,
+                self.icons,
+                &ids.id(&format!("{panel_id}-gradient")),
+                ids,
+            ),
+            text(18, 13.0, theme.title, self.lang.translate(trophy.title)),
+            text(
+                85,
+                10.5,
+                theme.text,
+                self.lang.translate(trophy.top_message),
+            ),
+            text(
+                97,
+                10.0,
+                theme.text,
+                &trophy.bottom_message(self.lang, &self.number_format),
+            ),
+            get_next_rank_bar(
+                &ids.id(&format!("{panel_id}-rank-progress")),
+                trophy.calculate_next_rank_percentage(),
+                theme.next_rank_bar,
+                (self.animation != Animation::None).then(|| ids.id("bar")),
+            ),
+        ]
+        .concat();
+        let mut children = self.fade_in(ids, children, (x + y) / size.max(1));
+
+        if self.animation == Animation::Shine && trophy.rank.first_letter() == "S" {
+            children.push_str(
+                &Tag::new("rect")
+                    .attr("class", ids.id("shine"))
+                    .attr("x", -SHINE_WIDTH)
+                    .attr("width", SHINE_WIDTH)
+                    .attr("height", panel_size)
+                    .attr("fill", format!("url(#{})", ids.id("shine-gradient")))
+                    .empty(),
+            );
+        }
+
+        Tag::new("svg")
+            .attr("x", x)
+            .attr("y", y)
+            .attr("width", size)
+            .attr("height", size)
+            .attr("viewBox", format!("0 0 {panel_size} {panel_size}"))
+            .attr("fill", "none")
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .attr("role", "img")
+            .attr("aria-labelledby", &title_id)
+            .wrap(&children)
+    }
+
+    fn scaled(&self, value: i32) -> i32 {
+        (value as f64 * self.panel_size as f64 / DEFAULT_PANEL_SIZE as f64).round() as i32
+    }
+
+    fn panel_frame(
+        &self,
+        width: i32,
+        height: i32,
+        theme: &Theme,
+        no_frame: bool,
+        frame_color: &str,
+    ) -> String {
+        Tag::new("rect")
+            .attr("x", 0.5)
+            .attr("y", 0.5)
+            .attr("rx", 4.5)
+            .attr("width", width - 1)
+            .attr("height", height - 1)
+            .attr("stroke", frame_color)
+            .attr("fill", theme.background)
+            .attr("stroke-opacity", if no_frame { "0" } else { "1" })
+            .attr("fill-opacity", if self.no_background { "0" } else { "1" })
+            .empty()
+    }
+
+    fn trophy_label(&self, trophy: &Trophy) -> String {
+        self.lang.format(
+            "{title}: rank {rank}, {message}, {score}",
+            &[
+                ("title", &self.lang.translate(trophy.title)),
+                ("rank", &trophy.rank.as_str()),
+                ("message", &self.lang.translate(trophy.top_message)),
+                (
+                    "score",
+                    &trophy.bottom_message(self.lang, &self.number_format),
+                ),
+            ],
+        )
+    }
+}
+
+struct Tag {
+    name: &'static str,
+    markup: String,
+}
+
+impl Tag {
+    fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            markup: format!("<{name}"),
+        }
+    }
+
+    fn attr(mut self, name: &str, value: impl Display) -> Self {
+        let _ = write!(
+            self.markup,
+            " {name}=\"{}\"",
+            escape_xml(&value.to_string())
+        );
+        self
+    }
+
+    fn empty(mut self) -> String {
+        self.markup.push_str("/>");
+        self.markup
+    }
+
+    fn text(self, text: &str) -> String {
+        let escaped = escape_xml(text);
+        self.wrap(&escaped)
+    }
+
+    fn wrap(mut self, children: &str) -> String {
+        let _ = write!(self.markup, ">{children}</{}>", self.name);
+        self.markup
+    }
+}
+
+struct Ids {
+    prefix: String,
+}
+
+impl Ids {
+    fn new(seed: &str) -> Self {
+        // FNV-1a keeps prefixes stable across processes, unlike the std hasher.
+        let hash = seed.bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
+
+        Self {
+            prefix: format!("tp{hash:08x}-"),
+        }
+    }
+
+    fn id(&self, local: &str) -> String {
+        let mut id = self.prefix.clone();
+        id.extend(local.chars().map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        }));
+        id
+    }
+}
+
+pub fn escape_xml(value: &str) -> Cow<'_, str> {
+    if !value.contains(['&', '<', '>', '"', '\'']) {
+        return Cow::Borrowed(value);
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 16);
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn text_tag(x: i32, y: i32, anchor: &str, font_size: f64, fill: &str) -> Tag {
+    Tag::new("text")
+        .attr("x", x)
+        .attr("y", y)
+        .attr("text-anchor", anchor)
+        .attr("font-family", FONT_FAMILY)
+        .attr("font-weight", "bold")
+        .attr("font-size", font_size)
+        .attr("fill", fill)
+}
+
+// Wide (CJK) glyphs take roughly a full em; Latin text averages a bit over half.
+fn approximate_text_width(text: &str, font_size: f64) -> i32 {
+    let ems = text
+        .chars()
+        .map(|c| if c.is_ascii() { 0.6 } else { 1.0 })
+        .sum::<f64>();
+    (ems * font_size).ceil() as i32
+}
+
+fn difficulty_label(scale: f64, lang: Lang) -> Option<String> {
+    if (scale - DEFAULT_SCALE).abs() < f64::EPSILON {
+        None
+    } else if (scale - EASY_SCALE).abs() < f64::EPSILON {
+        Some(lang.translate("difficulty: easy").to_string())
+    } else if (scale - HARD_SCALE).abs() < f64::EPSILON {
+        Some(lang.translate("difficulty: hard").to_string())
+    } else {
+        Some(lang.format("difficulty: ×{scale}", &[("scale", &scale)]))
+    }
 }
 
 fn get_row(trophy_count: usize, max_column: i32, max_row: i32) -> i32 {
@@ -138,107 +1226,205 @@ fn get_height(panel_size: i32, margin_height: i32, row: i32) -> i32 {
     panel_size * row + margin_height * (row - 1)
 }
 
-fn render_trophy(
-    trophy: &Trophy,
-    theme: &Theme,
-    x: i32,
-    y: i32,
-    panel_size: i32,
-    no_background: bool,
-    no_frame: bool,
+fn get_next_rank_bar(
+    progress_id: &str,
+    percentage: f64,
+    color: &str,
+    class: Option<String>,
 ) -> String {
-    let next_rank_bar = get_next_rank_bar(
-        trophy.title,
-        trophy.calculate_next_rank_percentage(),
-        theme.next_rank_bar,
-    );
-
-    let trophy_icon = get_trophy_icon(theme, trophy.rank);
-
-    let frame_opacity = if no_frame { "0" } else { "1" };
-    let background_opacity = if no_background { "0" } else { "1" };
-
-    format!(
-        "\n        <svg\n          x=\"{x}\"\n          y=\"{y}\"\n          width=\"{panel_size}\"\n          height=\"{panel_size}\"\n          viewBox=\"0 0 {panel_size} {panel_size}\"\n          fill=\"none\"\n          xmlns=\"http://www.w3.org/2000/svg\"\n        >\n          <rect\n            x=\"0.5\"\n            y=\"0.5\"\n            rx=\"4.5\"\n            width=\"{}\"\n            height=\"{}\"\n            stroke=\"#e1e4e8\"\n            fill=\"{}\"\n            stroke-opacity=\"{frame_opacity}\"\n            fill-opacity=\"{background_opacity}\"\n          />\n          {trophy_icon}\n          <text x=\"50%\" y=\"18\" text-anchor=\"middle\" font-family=\"Segoe UI,Helvetica,Arial,sans-serif,Apple Color Emoji,Segoe UI Emoji\" font-weight=\"bold\" font-size=\"13\" fill=\"{}\">{}</text>\n          <text x=\"50%\" y=\"85\" text-anchor=\"middle\" font-family=\"Segoe UI,Helvetica,Arial,sans-serif,Apple Color Emoji,Segoe UI Emoji\" font-weight=\"bold\" font-size=\"10.5\" fill=\"{}\">{}</text>\n          <text x=\"50%\" y=\"97\" text-anchor=\"middle\" font-family=\"Segoe UI,Helvetica,Arial,sans-serif,Apple Color Emoji,Segoe UI Emoji\" font-weight=\"bold\" font-size=\"10\" fill=\"{}\">{}</text>\n          {next_rank_bar}\n        </svg>\n        ",
-        panel_size - 1,
-        panel_size - 1,
-        theme.background,
-        theme.title,
-        trophy.title,
-        theme.text,
-        trophy.top_message,
-        theme.text,
-        trophy.bottom_message,
-    )
-}
-
-fn get_next_rank_bar(title: &str, percentage: f64, color: &str) -> String {
     let max_width = 80.0;
     let progress_width = max_width * percentage;
 
-    format!(
-        "\n    <style>\n    @keyframes {title}RankAnimation {{\n      from {{\n        width: 0px;\n      }}\n      to {{\n        width: {progress_width}px;\n      }}\n    }}\n    #{title}-rank-progress{{\n      animation: {title}RankAnimation 1s forwards ease-in-out;\n    }}\n    </style>\n    <rect\n      x=\"15\"\n      y=\"101\"\n      rx=\"1\"\n      width=\"{max_width}\"\n      height=\"3.2\"\n      opacity=\"0.3\"\n      fill=\"{color}\"\n    />\n    <rect\n      id=\"{title}-rank-progress\"\n      x=\"15\"\n      y=\"101\"\n      rx=\"1\"\n      height=\"3.2\"\n      fill=\"{color}\"\n    />\n  "
-    )
+    let track = Tag::new("rect")
+        .attr("x", 15)
+        .attr("y", 101)
+        .attr("rx", 1)
+        .attr("width", max_width)
+        .attr("height", 3.2)
+        .attr("opacity", 0.3)
+        .attr("fill", color)
+        .empty();
+    let mut progress = Tag::new("rect").attr("id", progress_id);
+    if let Some(class) = class {
+        progress = progress.attr("class", class);
+    }
+
+    track
+        + &progress
+            .attr("x", 15)
+            .attr("y", 101)
+            .attr("rx", 1)
+            .attr("width", progress_width)
+            .attr("height", 3.2)
+            .attr("fill", color)
+            .empty()
 }
 
-fn get_trophy_icon(theme: &Theme, rank: Rank) -> String {
-    let mut color = theme.default_rank_base;
-    let mut rank_color = theme.default_rank_text;
-    let mut background_icon = String::new();
-    let mut gradation_color = format!(
-        "\n      <stop offset=\"0%\" stop-color=\"{}\"/>\n      <stop offset=\"50%\" stop-color=\"{}\"/>\n      <stop offset=\"100%\" stop-color=\"{}\"/>\n  ",
-        theme.default_rank_base, theme.default_rank_base, theme.default_rank_shadow
-    );
+type RankPalette = (
+    &'static str,
+    &'static str,
+    [(&'static str, &'static str); 3],
+    bool,
+);
 
+// Returns the trophy base color, rank letter color, gradient stops and whether a laurel is drawn.
+fn rank_palette(theme: &Theme, rank: Rank) -> RankPalette {
     if rank == Rank::Secret {
-        rank_color = theme.secret_rank_text;
-        gradation_color = format!(
-            "\n    <stop offset=\"0%\" stop-color=\"{}\"/>\n    <stop offset=\"50%\" stop-color=\"{}\"/>\n    <stop offset=\"100%\" stop-color=\"{}\"/>\n    ",
-            theme.secret_rank_1, theme.secret_rank_2, theme.secret_rank_3
-        );
+        (
+            theme.default_rank_base,
+            theme.secret_rank_text,
+            [
+                ("0%", theme.secret_rank_1),
+                ("50%", theme.secret_rank_2),
+                ("100%", theme.secret_rank_3),
+            ],
+            false,
+        )
     } else if rank.first_letter() == "S" {
-        color = theme.s_rank_base;
-        rank_color = theme.s_rank_text;
-        background_icon = leaf_icon(theme.laurel);
-        gradation_color = format!(
-            "\n    <stop offset=\"0%\" stop-color=\"{color}\"/>\n    <stop offset=\"70%\" stop-color=\"{color}\"/>\n    <stop offset=\"100%\" stop-color=\"{}\"/>\n    ",
-            theme.s_rank_shadow
-        );
+        (
+            theme.s_rank_base,
+            theme.s_rank_text,
+            [
+                ("0%", theme.s_rank_base),
+                ("70%", theme.s_rank_base),
+                ("100%", theme.s_rank_shadow),
+            ],
+            true,
+        )
     } else if rank.first_letter() == "A" {
-        color = theme.a_rank_base;
-        rank_color = theme.a_rank_text;
-        background_icon = leaf_icon(theme.laurel);
-        gradation_color = format!(
-            "\n    <stop offset=\"0%\" stop-color=\"{color}\"/>\n    <stop offset=\"70%\" stop-color=\"{color}\"/>\n    <stop offset=\"100%\" stop-color=\"{}\"/>\n    ",
-            theme.a_rank_shadow
-        );
+        (
+            theme.a_rank_base,
+            theme.a_rank_text,
+            [
+                ("0%", theme.a_rank_base),
+                ("70%", theme.a_rank_base),
+                ("100%", theme.a_rank_shadow),
+            ],
+            true,
+        )
     } else if rank == Rank::B {
-        color = theme.b_rank_base;
-        rank_color = theme.b_rank_text;
-        gradation_color = format!(
-            "\n    <stop offset=\"0%\" stop-color=\"{color}\"/>\n    <stop offset=\"70%\" stop-color=\"{color}\"/>\n    <stop offset=\"100%\" stop-color=\"{}\"/>\n    ",
-            theme.b_rank_shadow
-        );
+        (
+            theme.b_rank_base,
+            theme.b_rank_text,
+            [
+                ("0%", theme.b_rank_base),
+                ("70%", theme.b_rank_base),
+                ("100%", theme.b_rank_shadow),
+            ],
+            false,
+        )
+    } else {
+        (
+            theme.default_rank_base,
+            theme.default_rank_text,
+            [
+                ("0%", theme.default_rank_base),
+                ("50%", theme.default_rank_base),
+                ("100%", theme.default_rank_shadow),
+            ],
+            false,
+        )
     }
+}
 
-    let icon = format!(
-        "\n    <path d=\"M7 10h2v4H7v-4z\"/>\n    <path d=\"M10 11c0 .552-.895 1-2 1s-2-.448-2-1 .895-1 2-1 2 .448 2 1z\"/>\n    <path fill-rule=\"evenodd\" d=\"M12.5 3a2 2 0 1 0 0 4 2 2 0 0 0 0-4zm-3 2a3 3 0 1 1 6 0 3 3 0 0 1-6 0zm-6-2a2 2 0 1 0 0 4 2 2 0 0 0 0-4zm-3 2a3 3 0 1 1 6 0 3 3 0 0 1-6 0z\"/>\n    <path d=\"M3 1h10c-.495 3.467-.5 10-5 10S3.495 4.467 3 1zm0 15a1 1 0 0 1 1-1h8a1 1 0 0 1 1 1H3zm2-1a1 1 0 0 1 1-1h4a1 1 0 0 1 1 1H5z\"/>\n    <circle cx=\"8\" cy=\"6\" r=\"4\" fill=\"{}\" />\n    <text x=\"6\" y=\"8\" font-family=\"Courier, Monospace\" font-size=\"7\" fill=\"{rank_color}\">{}</text>\n  ",
-        theme.icon_circle,
-        rank.first_letter()
-    );
+fn get_trophy_icon(
+    theme: &Theme,
+    trophy: &Trophy,
+    icons: IconSet,
+    gradient_id: &str,
+    ids: &Ids,
+) -> String {
+    let rank = trophy.rank;
+    let (color, rank_color, stops, laurel) = rank_palette(theme, rank);
+    let letter = |x: f64, y: f64, size: f64| {
+        Tag::new("text")
+            .attr("x", x)
+            .attr("y", y)
+            .attr("font-family", "Courier, Monospace")
+            .attr("font-size", size)
+            .attr("fill", rank_color)
+            .text(rank.first_letter())
+    };
+    let glyph = |name: &str, size: i32| {
+        Tag::new("use")
+            .attr("href", format!("#{}", ids.id(&glyph_symbol(name))))
+            .attr("width", size)
+            .attr("height", size)
+            .empty()
+    };
 
-    let option_rank_icon =
-        get_small_trophy_icon(&icon, color, rank.as_str().len().saturating_sub(1));
+    let category = trophy.icon.filter(|icon| glyph_template(icon).is_some());
+    let icon = match (icons, category) {
+        (IconSet::Minimal, _) => [glyph(MEDAL_GLYPH, 16), letter(5.9, 13.0, 7.0)].concat(),
+        // Category glyphs fill the cup's area, so the rank moves to a badge in the corner.
+        (IconSet::Category, Some(name)) => [
+            glyph(name, 15),
+            Tag::new("circle")
+                .attr("cx", 14)
+                .attr("cy", 13)
+                .attr("r", 3.2)
+                .attr("fill", theme.icon_circle)
+                .empty(),
+            letter(12.6, 14.6, 4.6),
+        ]
+        .concat(),
+        _ => [
+            Tag::new("use")
+                .attr("href", format!("#{}", ids.id(TROPHY_SYMBOL)))
+                .attr("width", 30)
+                .attr("height", 30)
+                .empty(),
+            letter(6.0, 8.0, 7.0),
+        ]
+        .concat(),
+    };
 
-    let mut output = String::with_capacity(1_000 + background_icon.len() + option_rank_icon.len());
-    output.push_str(&background_icon);
-    output.push_str(&option_rank_icon);
+    let gradient = stops
+        .iter()
+        .map(|(offset, stop_color)| {
+            Tag::new("stop")
+                .attr("offset", offset)
+                .attr("stop-color", stop_color)
+                .empty()
+        })
+        .collect::<String>();
 
-    let _ = write!(
-        output,
-        "\n  <defs>\n    <linearGradient id=\"{}\" gradientTransform=\"rotate(45)\">\n    {gradation_color}\n    </linearGradient>\n  </defs>\n  <svg x=\"28\" y=\"20\" width=\"100\" height=\"100\" viewBox=\"0 0 30 30\" fill=\"url(#{})\" xmlns=\"http://www.w3.org/2000/svg\">\n    {icon}\n  </svg>\n  ",
-        rank.as_str(),
-        rank.as_str(),
+    let mut output = String::with_capacity(2_000);
+    if laurel && icons != IconSet::Minimal {
+        output.push_str(
+            &Tag::new("use")
+                .attr("href", format!("#{}", ids.id(LAUREL_SYMBOL)))
+                .attr("width", "90pt")
+                .attr("height", "90pt")
+                .attr("fill", theme.laurel)
+                .empty(),
+        );
+    }
+    if icons != IconSet::Minimal {
+        output.push_str(&get_small_trophy_icon(
+            &icon,
+            color,
+            rank.as_str().len().saturating_sub(1),
+        ));
+    }
+    output.push_str(
+        &Tag::new("defs").wrap(
+            &Tag::new("linearGradient")
+                .attr("id", gradient_id)
+                .attr("gradientTransform", "rotate(45)")
+                .wrap(&gradient),
+        ),
+    );
+    output.push_str(
+        &Tag::new("svg")
+            .attr("x", 28)
+            .attr("y", 20)
+            .attr("width", 100)
+            .attr("height", 100)
+            .attr("viewBox", "0 0 30 30")
+            .attr("fill", format!("url(#{gradient_id})"))
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .wrap(&icon),
     );
 
     output
@@ -249,9 +1435,15 @@ fn get_small_trophy_icon(icon: &str, color: &str, count: usize) -> String {
     let right_x_position = 68;
 
     let render_icon = |x: i32| {
-        format!(
-            "<svg x=\"{x}\" y=\"35\" width=\"65\" height=\"65\" viewBox=\"0 0 30 30\" fill=\"{color}\" xmlns=\"http://www.w3.org/2000/svg\">\n      {icon}\n    </svg>"
-        )
+        Tag::new("svg")
+            .attr("x", x)
+            .attr("y", 35)
+            .attr("width", 65)
+            .attr("height", 65)
+            .attr("viewBox", "0 0 30 30")
+            .attr("fill", color)
+            .attr("xmlns", "http://www.w3.org/2000/svg")
+            .wrap(icon)
     };
 
     match count {
@@ -265,15 +1457,88 @@ fn get_small_trophy_icon(icon: &str, color: &str, count: usize) -> String {
     }
 }
 
-fn leaf_icon(laurel: &str) -> String {
-    LEAF_ICON_TEMPLATE.replace("__LAUREL__", laurel)
+// Shapes referenced by `<use>` are defined once per card, and only when something uses them.
+// Paths carry no fill of their own, so each `<use>` colors them.
+fn symbols(ids: &Ids, theme: &Theme, body: &str) -> String {
+    let trophy = [
+        TROPHY_ICON_PATHS.to_string(),
+        Tag::new("circle")
+            .attr("cx", 8)
+            .attr("cy", 6)
+            .attr("r", 4)
+            .attr("fill", theme.icon_circle)
+            .empty(),
+    ]
+    .concat();
+
+    let glyphs = GLYPH_TEMPLATES.iter().map(|(name, template)| {
+        (
+            Cow::Owned(glyph_symbol(name)),
+            "0 0 16 16",
+            glyph_paths(template, theme),
+        )
+    });
+    let symbols = [
+        (Cow::Borrowed(LAUREL_SYMBOL), "0 0 100 100", laurel_paths()),
+        (Cow::Borrowed(TROPHY_SYMBOL), "0 0 30 30", trophy),
+    ]
+    .into_iter()
+    .chain(glyphs)
+    .filter_map(|(local, view_box, content)| {
+        let id = ids.id(&local);
+        body.contains(&format!("\"#{id}\"")).then(|| {
+            Tag::new("symbol")
+                .attr("id", id)
+                .attr("viewBox", view_box)
+                .attr("preserveAspectRatio", "xMidYMid meet")
+                .wrap(&content)
+        })
+    })
+    .collect::<String>();
+
+    if symbols.is_empty() {
+        return symbols;
+    }
+    Tag::new("defs").wrap(&symbols)
+}
+
+fn glyph_symbol(name: &str) -> String {
+    format!("icon-{name}")
+}
+
+fn glyph_template(name: &str) -> Option<&'static str> {
+    GLYPH_TEMPLATES
+        .iter()
+        .find(|(glyph, _)| *glyph == name)
+        .map(|(_, template)| *template)
+}
+
+// A glyph template's shapes without the wrapping `<svg>`; `__ACCENT__` takes the theme's
+// icon circle color and everything else inherits the rank gradient from the referencing `<use>`.
+fn glyph_paths(template: &str, theme: &Theme) -> String {
+    let start = template.find('>').map_or(0, |end| end + 1);
+    let end = template.rfind("</svg>").unwrap_or(template.len());
+
+    template[start..end]
+        .trim()
+        .replace("__ACCENT__", &escape_xml(theme.icon_circle))
+}
+
+// The laurel template without its wrapping `<svg>`, filled by the referencing `<use>`.
+fn laurel_paths() -> String {
+    let start = LEAF_ICON_TEMPLATE.find("<g ").unwrap_or_default();
+    let end = LEAF_ICON_TEMPLATE
+        .rfind("</g>")
+        .map_or(LEAF_ICON_TEMPLATE.len(), |end| end + "</g>".len());
+
+    LEAF_ICON_TEMPLATE[start..end].replace(" fill=\"__LAUREL__\"", "")
 }
 
 #[allow(dead_code)]
 pub fn render_cli_svg(user_info: &UserInfo, theme: &Theme) -> String {
     Card::new(
         Vec::new(),
-        Vec::new(),
+        RankFilter::default(),
         -1,
         10,
         DEFAULT_PANEL_SIZE + 5,
@@ -284,3 +1549,506 @@ pub fn render_cli_svg(user_info: &UserInfo, theme: &Theme) -> String {
     )
     .render(user_info, theme)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{Animation, Card, IconSet, Layout};
+    use crate::{
+        i18n::Lang,
+        models::UserInfo,
+        number::NumberFormat,
+        themes::{THEME_DEFAULT, THEME_DRACULA},
+        trophy::RankFilter,
+    };
+
+    fn element_ids(svg: &str) -> Vec<String> {
+        svg.split(" id=\"")
+            .skip(1)
+            .filter_map(|rest| rest.split('"').next())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn card_with(titles: &[&str]) -> Card {
+        Card::new(
+            titles.iter().map(|title| title.to_string()).collect(),
+            RankFilter::default(),
+            -1,
+            3,
+            110,
+            0,
+            0,
+            false,
+            false,
+        )
+    }
+
+    fn card() -> Card {
+        card_with(&[])
+    }
+
+    #[test]
+    fn opt_in_trophies_are_drawn_only_by_name() {
+        let user_info = UserInfo {
+            total_stargazers: 250,
+            total_forks: 120,
+            ..Default::default()
+        };
+
+        let default = card().render(&user_info, &THEME_DEFAULT);
+        assert!(default.contains("Stargazer"));
+        assert!(!default.contains("Ultra Forked"));
+
+        let excluding = card_with(&["-Stars"]).render(&user_info, &THEME_DEFAULT);
+        assert!(!excluding.contains("Ultra Forked"));
+
+        let named = card_with(&["Stars", "Forks"]).render(&user_info, &THEME_DEFAULT);
+        assert!(named.contains("Ultra Forked"));
+    }
+
+    #[test]
+    fn comparison_highlights_leader_and_keeps_ids_unique() {
+        let alice = UserInfo {
+            total_stargazers: 250,
+            ..Default::default()
+        };
+        let bob = UserInfo {
+            total_stargazers: 40,
+            ..Default::default()
+        };
+
+        let card = card_with(&["Stars"]);
+        let svg = card.render_comparison(("alice", &alice), ("bob", &bob), &THEME_DEFAULT);
+
+        assert!(svg.contains(">alice</text>"));
+        assert!(svg.contains(">bob</text>"));
+        assert!(svg.contains("-left-Stars-rank-progress\""));
+        assert!(svg.contains("-right-Stars-rank-progress\""));
+        let ids = element_ids(&svg);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+        let leader_frame = format!("stroke=\"{}\"", THEME_DEFAULT.next_rank_bar);
+        assert_eq!(svg.matches(&leader_frame).count(), 1);
+    }
+
+    #[test]
+    fn lang_translates_text_but_keeps_ids() {
+        let user_info = UserInfo {
+            total_commits: 2_000,
+            ..Default::default()
+        };
+
+        let card = card_with(&["Commits"]).with_lang(Lang::Ja);
+        let svg = card.render(&user_info, &THEME_DEFAULT);
+
+        assert!(svg.contains(">コミット</text>"));
+        assert!(svg.contains(">熟練コミッター</text>"));
+        assert!(svg.contains("-Commits-rank-progress\""));
+    }
+
+    #[test]
+    fn cards_and_panels_carry_accessible_text() {
+        let user_info = UserInfo {
+            total_commits: 3_000,
+            ..Default::default()
+        };
+
+        let card = card_with(&["Commits"]);
+        let svg = card.render(&user_info, &THEME_DEFAULT);
+
+        let ids = element_ids(&svg);
+        let (title_id, desc_id) = (&ids[0], &ids[1]);
+        assert!(svg.contains(&format!(
+            "role=\"img\" aria-labelledby=\"{title_id} {desc_id}\"><title id=\"{title_id}\">GitHub Profile Trophy</title>"
+        )));
+        assert!(svg.contains(&format!(
+            "<desc id=\"{desc_id}\">Commits: rank SS, Deep Committer, 3.0kpt</desc>"
+        )));
+        assert!(ids[2].ends_with("-Commits-title"));
+        assert!(svg.contains(&format!("aria-labelledby=\"{}\"", ids[2])));
+        assert!(svg.contains("prefers-reduced-motion: reduce"));
+
+        let still = card
+            .with_animation(Animation::None)
+            .render(&user_info, &THEME_DEFAULT);
+        assert!(!still.contains("@keyframes"));
+        assert!(still.contains("width=\"40\""));
+    }
+
+    #[test]
+    fn user_text_is_escaped() {
+        let user_info = UserInfo::default();
+        let svg = card().render_comparison(
+            ("<script>&", &user_info),
+            ("\"bob\"", &user_info),
+            &THEME_DEFAULT,
+        );
+
+        assert!(!svg.contains("<script>"));
+        assert!(svg.contains(">&lt;script&gt;&amp;</text>"));
+        assert!(svg.contains(">&quot;bob&quot;</text>"));
+    }
+
+    #[test]
+    fn ids_are_namespaced_per_card_and_references_resolve() {
+        let user_info = UserInfo {
+            total_stargazers: 250,
+            total_commits: 600,
+            ..Default::default()
+        };
+
+        let light = card().render(&user_info, &THEME_DEFAULT);
+        let dark = card().render(&user_info, &THEME_DRACULA);
+        assert_eq!(light, card().render(&user_info, &THEME_DEFAULT));
+
+        let light_ids = element_ids(&light);
+        let dark_ids = element_ids(&dark);
+        assert_eq!(
+            light_ids.iter().collect::<HashSet<_>>().len(),
+            light_ids.len()
+        );
+        assert!(
+            light_ids
+                .iter()
+                .all(|id| id.starts_with(&light_ids[0][..11]))
+        );
+        assert!(light_ids.iter().all(|id| !dark_ids.contains(id)));
+
+        for reference in light.split("url(#").skip(1) {
+            let id = reference.split(')').next().unwrap_or_default();
+            assert!(light_ids.iter().any(|known| known == id));
+        }
+    }
+
+    #[test]
+    fn layouts_render_expected_shapes() {
+        let user_info = UserInfo {
+            total_stargazers: 250,
+            total_commits: 600,
+            total_followers: 20,
+            ..Default::default()
+        };
+
+        let card = || card_with(&["Stars", "Commits", "Followers"]);
+        let list = card()
+            .with_layout(Layout::List)
+            .render(&user_info, &THEME_DEFAULT);
+        assert!(list.starts_with("<svg width=\"320\" height=\"96\""));
+        assert!(list.contains(">Stars</text>"));
+        assert!(list.contains(">S · 250pt</text>"));
+
+        let badge = card()
+            .with_layout(Layout::Badge)
+            .render(&user_info, &THEME_DEFAULT);
+        assert!(badge.contains("height=\"20\" viewBox="));
+        assert!(badge.contains(">Stars</text>"));
+        assert!(badge.contains(">S · Stargazer</text>"));
+        assert!(!badge.contains(">Commits</text>"));
+        assert!(!badge.contains("Commits: rank"));
+
+        let hero = card()
+            .with_layout(Layout::Hero)
+            .render(&user_info, &THEME_DEFAULT);
+        assert!(hero.starts_with("<svg width=\"330\" height=\"220\""));
+        assert!(hero.contains(">Stargazer</text>"));
+        assert!(hero.contains("width=\"220\" height=\"220\" viewBox=\"0 0 110 110\""));
+        assert_eq!(hero.matches("width=\"55\" height=\"55\"").count(), 2);
+    }
+
+    #[test]
+    fn hero_counts_trophies_that_do_not_fit() {
+        let user_info = UserInfo {
+            total_commits: 100_000,
+            total_followers: 100_000,
+            total_issues: 100_000,
+            total_organizations: 100_000,
+            total_pull_requests: 100_000,
+            total_reviews: 100_000,
+            total_stargazers: 100_000,
+            total_repositories: 100_000,
+            total_forks: 100_000,
+            total_gists: 100_000,
+            total_discussions: 100_000,
+            total_answers: 100_000,
+            total_sponsors: 100_000,
+            total_sponsoring: 100_000,
+            total_packages: 100_000,
+            language_count: 100_000,
+            ancient_account: 1,
+            joined_2020: 1,
+            og_account: 1,
+            ..Default::default()
+        };
+        let titles = crate::definitions::definitions()
+            .user
+            .iter()
+            .map(|definition| definition.title)
+            .collect::<Vec<_>>();
+        let card = || card_with(&titles).with_layout(Layout::Hero);
+
+        // 21 trophies: the hero, 15 icons and a marker standing in for the other 5.
+        let hero = card().render(&user_info, &THEME_DEFAULT);
+        assert!(hero.starts_with("<svg width=\"440\" height=\"220\""));
+        assert_eq!(hero.matches("width=\"55\" height=\"55\"").count(), 16);
+        assert!(hero.contains(">5 more trophies</title>"));
+        let desc = hero
+            .split("<desc")
+            .nth(1)
+            .unwrap()
+            .split("</desc>")
+            .next()
+            .unwrap();
+        assert_eq!(desc.matches(": rank ").count(), 16);
+        assert!(desc.ends_with("; 5 more trophies"));
+        assert!(hero.contains(">+5</text>"));
+
+        let hero = card()
+            .with_lang(Lang::Es)
+            .render(&user_info, &THEME_DEFAULT);
+        assert!(hero.contains(">5 trofeos más</title>"));
+    }
+
+    #[test]
+    fn layouts_honor_no_background_and_no_frame() {
+        let user_info = UserInfo {
+            total_commits: 600,
+            ..Default::default()
+        };
+
+        for layout in [Layout::List, Layout::Badge, Layout::Hero] {
+            let svg = Card::new(
+                Vec::new(),
+                RankFilter::default(),
+                -1,
+                3,
+                110,
+                0,
+                0,
+                true,
+                true,
+            )
+            .with_layout(layout)
+            .render(&user_info, &THEME_DEFAULT);
+            assert!(!svg.contains("fill-opacity=\"1\""), "{layout:?}");
+            assert!(!svg.contains("stroke-opacity=\"1\""), "{layout:?}");
+        }
+    }
+
+    #[test]
+    fn size_scales_panels_without_changing_inner_geometry() {
+        let user_info = UserInfo::default();
+        let svg = Card::new(
+            vec!["Stars".into(), "Commits".into()],
+            RankFilter::default(),
+            -1,
+            3,
+            220,
+            0,
+            0,
+            false,
+            false,
+        )
+        .render(&user_info, &THEME_DEFAULT);
+
+        assert!(svg.starts_with("<svg width=\"440\" height=\"220\""));
+        assert!(
+            svg.contains("x=\"220\" y=\"0\" width=\"220\" height=\"220\" viewBox=\"0 0 110 110\"")
+        );
+        assert!(svg.contains("<text x=\"55\" y=\"18\""));
+    }
+
+    #[test]
+    fn target_width_picks_column_count() {
+        let user_info = UserInfo::default();
+        let card = |target_width| {
+            Card::new(
+                Vec::new(),
+                RankFilter::default(),
+                2,
+                10,
+                110,
+                10,
+                0,
+                false,
+                false,
+            )
+            .with_target_width(Some(target_width))
+            .render(&user_info, &THEME_DEFAULT)
+        };
+
+        assert!(card(360).starts_with("<svg width=\"350\""));
+        assert!(card(100).starts_with("<svg width=\"110\""));
+        assert!(card(100_000).starts_with("<svg width=\"950\""));
+    }
+
+    #[test]
+    fn animations_share_one_stylesheet_per_card() {
+        let user_info = UserInfo {
+            total_stargazers: 250,
+            total_commits: 600,
+            total_followers: 20,
+            ..Default::default()
+        };
+
+        let render = |animation| {
+            card_with(&["Stars", "Commits", "Followers"])
+                .with_animation(animation)
+                .render(&user_info, &THEME_DEFAULT)
+        };
+
+        for animation in [Animation::Bar, Animation::Fade, Animation::Shine] {
+            let svg = render(animation);
+            assert_eq!(svg.matches("<style>").count(), 1, "{animation:?}");
+            assert!(svg.matches("class=\"tp").count() >= 3);
+        }
+
+        let fade = render(Animation::Fade);
+        assert!(fade.contains("animation-delay:0ms"));
+        assert!(fade.contains("animation-delay:160ms"));
+
+        let shine = render(Animation::Shine);
+        assert_eq!(shine.matches("-shine\" x=\"-40\"").count(), 1);
+        assert!(shine.contains("-shine-gradient\">"));
+
+        let still = render(Animation::None);
+        assert!(!still.contains("<style>"));
+        assert!(!still.contains("class="));
+        assert!(still.len() < render(Animation::Bar).len());
+    }
+
+    #[test]
+    fn icon_geometry_is_defined_once_per_card() {
+        let user_info = UserInfo {
+            total_stargazers: 2_500,
+            total_commits: 6_000,
+            total_followers: 2_000,
+            total_pull_requests: 600,
+            ..Default::default()
+        };
+
+        let svg = card().render(&user_info, &THEME_DRACULA);
+        let ids = element_ids(&svg);
+        let laurel = ids.iter().find(|id| id.ends_with("-laurel")).unwrap();
+        let trophy = ids.iter().find(|id| id.ends_with("-trophy")).unwrap();
+
+        assert_eq!(svg.matches("<symbol ").count(), 2);
+        assert_eq!(svg.matches(super::TROPHY_ICON_PATHS).count(), 1);
+        assert_eq!(svg.matches("potrace").count(), 0);
+        assert!(svg.matches(&format!("href=\"#{laurel}\"")).count() >= 2);
+        assert!(svg.matches(&format!("href=\"#{trophy}\"")).count() >= 8);
+        assert!(svg.contains(&format!("fill=\"{}\"", THEME_DRACULA.laurel)));
+
+        let empty = card().render(&UserInfo::default(), &THEME_DRACULA);
+        assert!(!empty.contains("-laurel\""));
+    }
+
+    #[test]
+    fn icon_sets_pick_category_glyphs_from_templates() {
+        let user_info = UserInfo {
+            total_stargazers: 2_500,
+            total_commits: 600,
+            total_followers: 20,
+            ..Default::default()
+        };
+
+        let category = card()
+            .with_icons(IconSet::Category)
+            .render(&user_info, &THEME_DRACULA);
+        let ids = element_ids(&category);
+        for glyph in ["star", "commit", "people"] {
+            let id = ids
+                .iter()
+                .find(|id| id.ends_with(&format!("-icon-{glyph}")))
+                .unwrap();
+            assert!(category.contains(&format!("href=\"#{id}\"")), "{glyph}");
+        }
+        assert!(!category.contains("__ACCENT__"));
+        assert!(category.contains(&format!("fill=\"{}\"", THEME_DRACULA.icon_circle)));
+
+        let minimal = card()
+            .with_icons(IconSet::Minimal)
+            .render(&user_info, &THEME_DRACULA);
+        assert!(minimal.contains("-icon-medal\""));
+        assert!(!minimal.contains("-laurel\""));
+        assert!(!minimal.contains("-trophy\""));
+        assert!(minimal.len() < category.len());
+
+        assert_eq!(IconSet::from_param(Some("Minimal")), IconSet::Minimal);
+        assert_eq!(IconSet::from_param(Some("bogus")), IconSet::Classic);
+    }
+
+    #[test]
+    fn every_builtin_icon_has_a_template() {
+        let definitions = crate::definitions::definitions();
+        for definition in definitions
+            .user
+            .iter()
+            .chain(&definitions.organization)
+            .chain(&definitions.repository)
+        {
+            let icon = definition.icon.unwrap_or_default();
+            assert!(
+                super::glyph_template(icon).is_some(),
+                "{}",
+                definition.title
+            );
+        }
+    }
+
+    #[test]
+    fn overflowing_text_shrinks_then_truncates() {
+        let card = card();
+
+        let (text, size) = card.fit_text("Stars", 13.0, 100.0);
+        assert_eq!((text.as_ref(), size), ("Stars", 13.0));
+
+        let (text, size) = card.fit_text("MultiLanguage", 13.0, 100.0);
+        assert_eq!(text, "MultiLanguage");
+        assert!((13.0 * super::MIN_TEXT_SCALE..13.0).contains(&size));
+
+        let (text, size) = card.fit_text("マルチリンガルプログラマー", 13.0, 100.0);
+        assert!(text.ends_with('…'));
+        assert_eq!(size, 13.0 * super::MIN_TEXT_SCALE);
+        assert!(card.text_width(&text, size) <= 100.0);
+
+        // Cards without `font=embed` draw their text as they always have.
+        let user_info = UserInfo {
+            total_stargazers: 123_456_789,
+            ..Default::default()
+        };
+        let card =
+            card.with_number_format(NumberFormat::from_params(Some("exact"), Some("sparkles")));
+        let plain = card.clone().render(&user_info, &THEME_DRACULA);
+        assert!(plain.contains(">123,456,789sparkles</text>"));
+        let embedded = card
+            .with_font(Some(crate::font::embedded()))
+            .render(&user_info, &THEME_DRACULA);
+        let message_size = |svg: &str| {
+            let end = svg.find(">123,456,789sparkles</text>").unwrap();
+            let start = svg[..end].rfind("font-size=\"").unwrap() + 11;
+            svg[start..end]
+                .split('"')
+                .next()
+                .unwrap()
+                .parse::<f64>()
+                .unwrap()
+        };
+        assert!(message_size(&embedded) < message_size(&plain));
+    }
+
+    #[test]
+    fn rate_limited_card_is_themed_and_localized() {
+        let svg = card()
+            .with_lang(Lang::Ja)
+            .with_scale(crate::constants::EASY_SCALE)
+            .render_rate_limited(&THEME_DRACULA, 42);
+
+        assert!(svg.contains("<title id="));
+        assert!(svg.contains(">リクエストが多すぎます</text>"));
+        assert!(svg.contains("42 秒後に再試行してください。"));
+        assert!(svg.contains(&format!("fill=\"{}\"", THEME_DRACULA.background)));
+        assert!(!svg.contains("<style>"));
+        assert!(!svg.contains("難易度"));
+    }
+}
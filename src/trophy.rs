@@ -1,6 +1,12 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::RangeInclusive};
 
-use crate::models::UserInfo;
+use crate::{
+    definitions::{Metric, ScoreFormat, TrophyDefinition, definitions},
+    i18n::Lang,
+    models::{MetricSource, OrganizationInfo, RepositoryInfo, UserInfo, format_months},
+    number::NumberFormat,
+    request::ParamError,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rank {
@@ -32,6 +38,14 @@ impl Rank {
         }
     }
 
+    pub fn from_name(name: &str) -> Option<Self> {
+        RANK_ORDER
+            .iter()
+            .copied()
+            .filter(|rank| *rank != Self::Unknown)
+            .find(|rank| rank.as_str().eq_ignore_ascii_case(name))
+    }
+
     pub fn first_letter(self) -> &'static str {
         match self {
             Self::Unknown => "?",
@@ -48,6 +62,153 @@ impl Rank {
     }
 }
 
+// Ranges are over `rank_order_index`, so a lower bound in rank is an upper bound in index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RankFilter {
+    include: Vec<RangeInclusive<usize>>,
+    exclude: Vec<RangeInclusive<usize>>,
+    minimum: Option<usize>,
+}
+
+impl RankFilter {
+    pub fn parse(ranks: &[String], min_rank: Option<&str>) -> Result<Self, ParamError> {
+        let mut filter = Self::default();
+
+        for rank in ranks {
+            match rank.strip_prefix('-') {
+                Some(excluded) => filter.exclude.push(parse_rank_range(excluded)?),
+                None => filter.include.push(parse_rank_range(rank)?),
+            }
+        }
+
+        if let Some(min_rank) = min_rank.map(str::trim).filter(|value| !value.is_empty()) {
+            let rank = parse_rank(min_rank)?;
+            filter.minimum = Some(rank_order_index(rank));
+        }
+
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty() && self.minimum.is_none()
+    }
+
+    // The `rank` and `min-rank` values that parse back into an equal filter.
+    pub fn canonical(&self) -> (Option<String>, Option<&'static str>) {
+        let format = |ranges: &[RangeInclusive<usize>], prefix: &str| {
+            let mut ranges = ranges.to_vec();
+            ranges.sort_by_key(|range| (*range.start(), *range.end()));
+            ranges.dedup();
+            ranges
+                .into_iter()
+                .map(|range| {
+                    let (high, low) = (RANK_ORDER[*range.start()], RANK_ORDER[*range.end()]);
+                    if high == low {
+                        format!("{prefix}{}", high.as_str())
+                    } else {
+                        format!("{prefix}{}..{}", low.as_str(), high.as_str())
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let ranks = [format(&self.include, ""), format(&self.exclude, "-")].concat();
+        (
+            (!ranks.is_empty()).then(|| ranks.join(",")),
+            self.minimum.map(|index| RANK_ORDER[index].as_str()),
+        )
+    }
+
+    pub fn matches(&self, rank: Rank) -> bool {
+        let index = rank_order_index(rank);
+        let ranked = rank != Rank::Unknown;
+
+        let included = self.include.is_empty()
+            || (ranked && self.include.iter().any(|range| range.contains(&index)));
+        let excluded = ranked && self.exclude.iter().any(|range| range.contains(&index));
+        let above_minimum = self
+            .minimum
+            .is_none_or(|minimum| ranked && index <= minimum);
+
+        included && !excluded && above_minimum
+    }
+}
+
+fn parse_rank(value: &str) -> Result<Rank, ParamError> {
+    Rank::from_name(value.trim()).ok_or_else(|| ParamError::InvalidRank {
+        value: value.to_string(),
+    })
+}
+
+fn parse_rank_range(value: &str) -> Result<RangeInclusive<usize>, ParamError> {
+    let lowest = rank_order_index(Rank::C);
+    let index = |rank: &str| parse_rank(rank).map(rank_order_index);
+    let invalid = || ParamError::InvalidRank {
+        value: value.to_string(),
+    };
+    let value = value.trim();
+
+    if let Some((from, to)) = value.split_once("..") {
+        let (from, to) = (
+            index(from).map_err(|_| invalid())?,
+            index(to).map_err(|_| invalid())?,
+        );
+        return Ok(from.min(to)..=from.max(to));
+    }
+
+    let range = if let Some(rank) = value.strip_prefix(">=") {
+        0..=index(rank)?
+    } else if let Some(rank) = value.strip_prefix("<=") {
+        index(rank)?..=lowest
+    } else if let Some(rank) = value.strip_prefix('>') {
+        let index = index(rank)?;
+        0..=index.checked_sub(1).ok_or_else(invalid)?
+    } else if let Some(rank) = value.strip_prefix('<') {
+        let index = index(rank)? + 1;
+        if index > lowest {
+            return Err(invalid());
+        }
+        index..=lowest
+    } else {
+        let index = index(value)?;
+        index..=index
+    };
+
+    Ok(range)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Rank,
+    Title,
+    Score,
+    Progress,
+    Alpha,
+}
+
+impl SortOrder {
+    pub fn from_param(value: Option<&str>) -> Self {
+        match value.map(str::to_ascii_lowercase).as_deref() {
+            Some("title") | Some("titles") => Self::Title,
+            Some("score") => Self::Score,
+            Some("progress") => Self::Progress,
+            Some("alpha") | Some("name") => Self::Alpha,
+            _ => Self::Rank,
+        }
+    }
+
+    pub fn as_param(self) -> &'static str {
+        match self {
+            Self::Rank => "rank",
+            Self::Title => "title",
+            Self::Score => "score",
+            Self::Progress => "progress",
+            Self::Alpha => "alpha",
+        }
+    }
+}
+
 pub const RANK_ORDER: [Rank; 10] = [
     Rank::Secret,
     Rank::Sss,
@@ -72,42 +233,38 @@ pub struct RankCondition {
 pub struct Trophy {
     pub rank_condition: Option<RankCondition>,
     pub rank: Rank,
-    pub top_message: String,
-    pub bottom_message: String,
+    pub top_message: &'static str,
     pub title: &'static str,
     pub filter_titles: &'static [&'static str],
     pub hidden: bool,
+    pub opt_in: bool,
+    pub icon: Option<&'static str>,
     score: i64,
+    scale: f64,
+    score_format: ScoreFormat,
+    bottom_override: Option<&'static str>,
     rank_conditions: &'static [RankCondition],
 }
 
 impl Trophy {
-    fn new(
-        score: i64,
-        title: &'static str,
-        filter_titles: &'static [&'static str],
-        hidden: bool,
-        rank_conditions: &'static [RankCondition],
-        bottom_override: Option<&'static str>,
-    ) -> Self {
+    fn new(score: i64, definition: &'static TrophyDefinition, scale: f64) -> Self {
         let mut trophy = Self {
             rank_condition: None,
             rank: Rank::Unknown,
-            top_message: "Unknown".to_string(),
-            bottom_message: abridge_score(score),
-            title,
-            filter_titles,
-            hidden,
+            top_message: "Unknown",
+            title: definition.title,
+            filter_titles: definition.filter_titles,
+            hidden: definition.hidden,
+            opt_in: definition.opt_in,
+            icon: definition.icon,
             score,
-            rank_conditions,
+            scale,
+            score_format: definition.score_format,
+            bottom_override: definition.bottom_override,
+            rank_conditions: definition.rank_conditions,
         };
 
         trophy.set_rank();
-
-        if let Some(bottom) = bottom_override {
-            trophy.bottom_message = bottom.to_string();
-        }
-
         trophy
     }
 
@@ -117,11 +274,36 @@ impl Trophy {
 
         if let Some(condition) = sorted
             .into_iter()
-            .find(|condition| self.score >= condition.required_score)
+            .find(|condition| self.score >= self.required_score(condition))
         {
             self.rank = condition.rank;
             self.rank_condition = Some(*condition);
-            self.top_message = condition.message.to_string();
+            self.top_message = condition.message;
+        }
+    }
+
+    fn required_score(&self, condition: &RankCondition) -> i64 {
+        if condition.rank == Rank::Secret {
+            return condition.required_score;
+        }
+
+        ((condition.required_score as f64) * self.scale)
+            .round()
+            .max(1.0) as i64
+    }
+
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    pub fn bottom_message(&self, lang: Lang, number_format: &NumberFormat) -> String {
+        if let Some(bottom) = self.bottom_override {
+            return lang.translate(bottom).to_string();
+        }
+
+        match self.score_format {
+            ScoreFormat::Points => number_format.format(self.score, lang),
+            ScoreFormat::Months => format_months(self.score, lang),
         }
     }
 
@@ -151,12 +333,13 @@ impl Trophy {
             None => return 1.0,
         };
 
-        let distance = next_condition.required_score - current_condition.required_score;
+        let current_required = self.required_score(&current_condition);
+        let distance = self.required_score(&next_condition) - current_required;
         if distance <= 0 {
             return 1.0;
         }
 
-        let progress = self.score - current_condition.required_score;
+        let progress = self.score - current_required;
         (progress as f64 / distance as f64).clamp(0.0, 1.0)
     }
 }
@@ -167,42 +350,43 @@ pub struct TrophyList {
 }
 
 impl TrophyList {
-    pub fn new(user_info: &UserInfo) -> Self {
-        let mut trophies = vec![
-            total_star_trophy(user_info.total_stargazers),
-            total_commit_trophy(user_info.total_commits),
-            total_follower_trophy(user_info.total_followers),
-            total_issue_trophy(user_info.total_issues),
-            total_pull_request_trophy(user_info.total_pull_requests),
-            total_repository_trophy(user_info.total_repositories),
-            total_reviews_trophy(user_info.total_reviews),
-        ];
-
-        let is_all_s_rank = if trophies
-            .iter()
-            .all(|trophy| trophy.rank.as_str().starts_with('S'))
-        {
-            1
-        } else {
-            0
-        };
+    pub fn new(user_info: &UserInfo, scale: f64) -> Self {
+        Self::from_definitions(&definitions().user, user_info, scale)
+    }
 
-        trophies.extend([
-            all_super_rank_trophy(is_all_s_rank),
-            multiple_lang_trophy(user_info.language_count),
-            long_time_account_trophy(user_info.duration_year),
-            ancient_account_trophy(user_info.ancient_account),
-            og_account_trophy(user_info.og_account),
-            joined_2020_trophy(user_info.joined_2020),
-            multiple_organizations_trophy(user_info.total_organizations),
-            account_duration_trophy(user_info.duration_days),
-        ]);
+    pub fn new_organization(organization_info: &OrganizationInfo, scale: f64) -> Self {
+        Self::from_definitions(&definitions().organization, organization_info, scale)
+    }
 
-        Self { trophies }
+    pub fn new_repository(repository_info: &RepositoryInfo, scale: f64) -> Self {
+        Self::from_definitions(&definitions().repository, repository_info, scale)
     }
 
-    pub fn len(&self) -> usize {
-        self.trophies.len()
+    fn from_definitions(
+        trophy_definitions: &'static [TrophyDefinition],
+        source: &impl MetricSource,
+        scale: f64,
+    ) -> Self {
+        let mut trophies: Vec<Trophy> = Vec::with_capacity(trophy_definitions.len());
+
+        for definition in trophy_definitions {
+            let score = match definition.metric {
+                Metric::Field(name) => source.metric(name).unwrap_or(0),
+                Metric::AllSuperRank(required_titles) => {
+                    let is_all_s_rank = required_titles.iter().all(|title| {
+                        trophies
+                            .iter()
+                            .find(|trophy| trophy.title == *title)
+                            .is_some_and(|trophy| trophy.rank.as_str().starts_with('S'))
+                    });
+                    i64::from(is_all_s_rank)
+                }
+            };
+
+            trophies.push(Trophy::new(score, definition, scale));
+        }
+
+        Self { trophies }
     }
 
     pub fn items(&self) -> &[Trophy] {
@@ -214,6 +398,10 @@ impl TrophyList {
             .retain(|trophy| !trophy.hidden || trophy.rank != Rank::Unknown);
     }
 
+    pub fn filter_by_opt_in(&mut self) {
+        self.trophies.retain(|trophy| !trophy.opt_in);
+    }
+
     pub fn filter_by_titles(&mut self, titles: &[String]) {
         let include: HashSet<&str> = titles.iter().map(String::as_str).collect();
 
@@ -225,20 +413,8 @@ impl TrophyList {
         });
     }
 
-    pub fn filter_by_ranks(&mut self, ranks: &[String]) {
-        if ranks.iter().any(|rank| rank.contains('-')) {
-            let excluded: HashSet<&str> = ranks
-                .iter()
-                .filter_map(|rank| rank.strip_prefix('-'))
-                .collect();
-            self.trophies
-                .retain(|trophy| !excluded.contains(trophy.rank.as_str()));
-            return;
-        }
-
-        let included: HashSet<&str> = ranks.iter().map(String::as_str).collect();
-        self.trophies
-            .retain(|trophy| included.contains(trophy.rank.as_str()));
+    pub fn filter_by_ranks(&mut self, filter: &RankFilter) {
+        self.trophies.retain(|trophy| filter.matches(trophy.rank));
     }
 
     pub fn filter_by_exclusion_titles(&mut self, titles: &[String]) {
@@ -259,592 +435,235 @@ impl TrophyList {
         self.trophies
             .sort_by_key(|trophy| rank_order_index(trophy.rank));
     }
-}
 
-pub fn abridge_score(score: i64) -> String {
-    let abs = score.abs();
-    if abs < 1 {
-        return "0pt".to_string();
+    // Every order is a stable sort over the rank order, so ties keep the rank order and then
+    // the definition order.
+    pub fn sort(&mut self, order: SortOrder, titles: &[String]) {
+        self.sort_by_rank();
+
+        match order {
+            SortOrder::Rank => {}
+            SortOrder::Title => self.trophies.sort_by_key(|trophy| {
+                titles
+                    .iter()
+                    .position(|title| trophy.filter_titles.contains(&title.as_str()))
+                    .unwrap_or(titles.len())
+            }),
+            SortOrder::Score => self
+                .trophies
+                .sort_by_key(|trophy| std::cmp::Reverse(trophy.score())),
+            SortOrder::Progress => self.trophies.sort_by(|left, right| {
+                right
+                    .calculate_next_rank_percentage()
+                    .total_cmp(&left.calculate_next_rank_percentage())
+            }),
+            SortOrder::Alpha => self
+                .trophies
+                .sort_by_cached_key(|trophy| trophy.title.to_lowercase()),
+        }
     }
 
-    if abs > 999 {
-        let signed = if score < 0 {
-            -((abs as f64) / 1000.0)
-        } else {
-            (abs as f64) / 1000.0
-        };
-        return format!("{signed:.1}kpt");
+    pub fn truncate(&mut self, limit: usize) {
+        self.trophies.truncate(limit);
     }
-
-    format!("{score}pt")
 }
 
-fn rank_order_index(rank: Rank) -> usize {
+pub fn rank_order_index(rank: Rank) -> usize {
     RANK_ORDER
         .iter()
         .position(|item| *item == rank)
         .unwrap_or(RANK_ORDER.len() - 1)
 }
 
-const CONDITION_SECRET_RAINBOW: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "Rainbow Lang User",
-    required_score: 10,
-}];
-
-const CONDITION_SECRET_ALL_S: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "S Rank Hacker",
-    required_score: 1,
-}];
-
-const CONDITION_SECRET_JOINED_2020: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "Everything started...",
-    required_score: 1,
-}];
-
-const CONDITION_SECRET_ANCIENT: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "Ancient User",
-    required_score: 1,
-}];
-
-const CONDITION_SECRET_LONG_TIME: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "Village Elder",
-    required_score: 10,
-}];
-
-const CONDITION_SECRET_ORG: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "Jack of all Trades",
-    required_score: 3,
-}];
-
-const CONDITION_SECRET_OG: [RankCondition; 1] = [RankCondition {
-    rank: Rank::Secret,
-    message: "OG User",
-    required_score: 1,
-}];
-
-const CONDITION_REVIEWS: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "God Reviewer",
-        required_score: 70,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Deep Reviewer",
-        required_score: 57,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Super Reviewer",
-        required_score: 45,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Ultra Reviewer",
-        required_score: 30,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Hyper Reviewer",
-        required_score: 20,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "Active Reviewer",
-        required_score: 8,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Intermediate Reviewer",
-        required_score: 3,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "New Reviewer",
-        required_score: 1,
-    },
-];
-
-const CONDITION_ACCOUNT_DURATION: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "Seasoned Veteran",
-        required_score: 70,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Grandmaster",
-        required_score: 55,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Master Dev",
-        required_score: 40,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Expert Dev",
-        required_score: 28,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Experienced Dev",
-        required_score: 18,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "Intermediate Dev",
-        required_score: 11,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Junior Dev",
-        required_score: 6,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "Newbie",
-        required_score: 2,
-    },
-];
-
-const CONDITION_STARS: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "Super Stargazer",
-        required_score: 2000,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "High Stargazer",
-        required_score: 700,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Stargazer",
-        required_score: 200,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Super Star",
-        required_score: 100,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "High Star",
-        required_score: 50,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "You are a Star",
-        required_score: 30,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Middle Star",
-        required_score: 10,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "First Star",
-        required_score: 1,
-    },
-];
-
-const CONDITION_COMMITS: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "God Committer",
-        required_score: 4000,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Deep Committer",
-        required_score: 2000,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Super Committer",
-        required_score: 1000,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Ultra Committer",
-        required_score: 500,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Hyper Committer",
-        required_score: 200,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "High Committer",
-        required_score: 100,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Middle Committer",
-        required_score: 10,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "First Commit",
-        required_score: 1,
-    },
-];
-
-const CONDITION_FOLLOWERS: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "Super Celebrity",
-        required_score: 1000,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Ultra Celebrity",
-        required_score: 400,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Hyper Celebrity",
-        required_score: 200,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Famous User",
-        required_score: 100,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Active User",
-        required_score: 50,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "Dynamic User",
-        required_score: 20,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Many Friends",
-        required_score: 10,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "First Friend",
-        required_score: 1,
-    },
-];
-
-const CONDITION_ISSUES: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "God Issuer",
-        required_score: 1000,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Deep Issuer",
-        required_score: 500,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Super Issuer",
-        required_score: 200,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Ultra Issuer",
-        required_score: 100,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Hyper Issuer",
-        required_score: 50,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "High Issuer",
-        required_score: 20,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Middle Issuer",
-        required_score: 10,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "First Issue",
-        required_score: 1,
-    },
-];
-
-const CONDITION_PULL_REQUESTS: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "God Puller",
-        required_score: 1000,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Deep Puller",
-        required_score: 500,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Super Puller",
-        required_score: 200,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Ultra Puller",
-        required_score: 100,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Hyper Puller",
-        required_score: 50,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "High Puller",
-        required_score: 20,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Middle Puller",
-        required_score: 10,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "First Pull",
-        required_score: 1,
-    },
-];
-
-const CONDITION_REPOSITORIES: [RankCondition; 8] = [
-    RankCondition {
-        rank: Rank::Sss,
-        message: "God Repo Creator",
-        required_score: 50,
-    },
-    RankCondition {
-        rank: Rank::Ss,
-        message: "Deep Repo Creator",
-        required_score: 45,
-    },
-    RankCondition {
-        rank: Rank::S,
-        message: "Super Repo Creator",
-        required_score: 40,
-    },
-    RankCondition {
-        rank: Rank::Aaa,
-        message: "Ultra Repo Creator",
-        required_score: 35,
-    },
-    RankCondition {
-        rank: Rank::Aa,
-        message: "Hyper Repo Creator",
-        required_score: 30,
-    },
-    RankCondition {
-        rank: Rank::A,
-        message: "High Repo Creator",
-        required_score: 20,
-    },
-    RankCondition {
-        rank: Rank::B,
-        message: "Middle Repo Creator",
-        required_score: 10,
-    },
-    RankCondition {
-        rank: Rank::C,
-        message: "First Repository",
-        required_score: 1,
-    },
-];
-
-fn multiple_lang_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "MultiLanguage",
-        &["MultipleLang", "MultiLanguage"],
-        true,
-        &CONDITION_SECRET_RAINBOW,
-        None,
-    )
-}
-
-fn all_super_rank_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "AllSuperRank",
-        &["AllSuperRank"],
-        true,
-        &CONDITION_SECRET_ALL_S,
-        Some("All S Rank"),
-    )
-}
-
-fn joined_2020_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Joined2020",
-        &["Joined2020"],
-        true,
-        &CONDITION_SECRET_JOINED_2020,
-        Some("Joined 2020"),
-    )
-}
+#[cfg(test)]
+mod tests {
+    use super::{Rank, RankFilter, SortOrder, TrophyList};
+    use crate::{
+        constants::{DEFAULT_SCALE, EASY_SCALE},
+        i18n::Lang,
+        models::{AccountAge, OrganizationInfo, UserInfo},
+        number::NumberFormat,
+    };
 
-fn ancient_account_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "AncientUser",
-        &["AncientUser"],
-        true,
-        &CONDITION_SECRET_ANCIENT,
-        Some("Before 2010"),
-    )
-}
+    #[test]
+    fn organization_trophies_use_organization_thresholds() {
+        let organization_info = OrganizationInfo {
+            total_members: 120,
+            total_repositories: 0,
+            total_stargazers: 12_000,
+            total_sponsors: 0,
+            language_count: 4,
+            account_age: AccountAge {
+                years: 3,
+                months: 40,
+                days: 1_220,
+            },
+        };
 
-fn long_time_account_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "LongTimeUser",
-        &["LongTimeUser"],
-        true,
-        &CONDITION_SECRET_LONG_TIME,
-        None,
-    )
-}
+        let trophy_list = TrophyList::new_organization(&organization_info, DEFAULT_SCALE);
+        let rank_of = |title: &str| {
+            trophy_list
+                .items()
+                .iter()
+                .find(|trophy| trophy.title == title)
+                .map(|trophy| trophy.rank)
+        };
 
-fn multiple_organizations_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Organizations",
-        &["Organizations", "Orgs", "Teams"],
-        true,
-        &CONDITION_SECRET_ORG,
-        None,
-    )
-}
+        assert_eq!(rank_of("Stars"), Some(Rank::Ss));
+        assert_eq!(rank_of("Members"), Some(Rank::S));
+        assert_eq!(rank_of("Sponsors"), Some(Rank::Unknown));
+    }
 
-fn og_account_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "OGUser",
-        &["OGUser"],
-        true,
-        &CONDITION_SECRET_OG,
-        Some("Joined 2008"),
-    )
-}
+    #[test]
+    fn community_trophies_appear_once_ranked() {
+        let user_info = UserInfo {
+            total_forks: 120,
+            total_answers: 4,
+            ..Default::default()
+        };
 
-fn total_reviews_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Reviews",
-        &["Review", "Reviews"],
-        false,
-        &CONDITION_REVIEWS,
-        None,
-    )
-}
+        let mut trophy_list = TrophyList::new(&user_info, DEFAULT_SCALE);
+        trophy_list.filter_by_hidden();
+        let titles = trophy_list
+            .items()
+            .iter()
+            .map(|trophy| (trophy.title, trophy.rank))
+            .collect::<Vec<_>>();
 
-fn account_duration_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Experience",
-        &["Experience", "Duration", "Since"],
-        false,
-        &CONDITION_ACCOUNT_DURATION,
-        None,
-    )
-}
+        assert!(titles.contains(&("Forked", Rank::Aaa)));
+        assert!(titles.contains(&("Answers", Rank::B)));
+        assert!(!titles.iter().any(|(title, _)| *title == "Gists"));
+    }
 
-fn total_star_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Stars",
-        &["Star", "Stars"],
-        false,
-        &CONDITION_STARS,
-        None,
-    )
-}
+    #[test]
+    fn scale_lowers_tiered_thresholds_but_not_secret_ones() {
+        let user_info = UserInfo {
+            total_commits: 2_000,
+            og_account: 1,
+            ..Default::default()
+        };
 
-fn total_commit_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Commits",
-        &["Commit", "Commits"],
-        false,
-        &CONDITION_COMMITS,
-        None,
-    )
-}
+        let trophy_list = TrophyList::new(&user_info, EASY_SCALE);
+        let rank_of = |title: &str| {
+            trophy_list
+                .items()
+                .iter()
+                .find(|trophy| trophy.title == title)
+                .map(|trophy| trophy.rank)
+        };
 
-fn total_follower_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Followers",
-        &["Follower", "Followers"],
-        false,
-        &CONDITION_FOLLOWERS,
-        None,
-    )
-}
+        assert_eq!(rank_of("Commits"), Some(Rank::Sss));
+        assert_eq!(rank_of("OGUser"), Some(Rank::Secret));
+        assert_eq!(rank_of("Stars"), Some(Rank::Unknown));
+    }
 
-fn total_issue_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Issues",
-        &["Issue", "Issues"],
-        false,
-        &CONDITION_ISSUES,
-        None,
-    )
-}
+    #[test]
+    fn experience_shows_account_age() {
+        let user_info = UserInfo {
+            account_age: AccountAge {
+                years: 7,
+                months: 87,
+                days: 2_650,
+            },
+            ..Default::default()
+        };
 
-fn total_pull_request_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "PullRequest",
-        &["PR", "PullRequest", "Pulls", "Puller"],
-        false,
-        &CONDITION_PULL_REQUESTS,
-        None,
-    )
-}
+        let trophy_list = TrophyList::new(&user_info, DEFAULT_SCALE);
+        let experience = trophy_list
+            .items()
+            .iter()
+            .find(|trophy| trophy.title == "Experience")
+            .expect("experience trophy");
+
+        assert_eq!(experience.rank, Rank::Aa);
+        let number_format = NumberFormat::default();
+        assert_eq!(experience.bottom_message(Lang::En, &number_format), "7y 3m");
+        assert_eq!(
+            experience.bottom_message(Lang::Ja, &number_format),
+            "7年3ヶ月"
+        );
+    }
 
-fn total_repository_trophy(score: i64) -> Trophy {
-    Trophy::new(
-        score,
-        "Repositories",
-        &["Repo", "Repository", "Repositories"],
-        false,
-        &CONDITION_REPOSITORIES,
-        None,
-    )
-}
+    #[test]
+    fn sort_orders_are_stable_and_limit_keeps_the_top() {
+        let user_info = UserInfo {
+            total_stargazers: 250,
+            total_commits: 600,
+            total_followers: 20,
+            ..Default::default()
+        };
+        let titles = ["Followers", "Stars", "Commits"].map(String::from);
+
+        let order = |sort: SortOrder, limit: usize| {
+            let mut trophy_list = TrophyList::new(&user_info, DEFAULT_SCALE);
+            trophy_list.filter_by_titles(&titles);
+            trophy_list.sort(sort, &titles);
+            trophy_list.truncate(limit);
+            trophy_list
+                .items()
+                .iter()
+                .map(|trophy| trophy.title)
+                .collect::<Vec<_>>()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::abridge_score;
+        assert_eq!(
+            order(SortOrder::Title, 3),
+            ["Followers", "Stars", "Commits"]
+        );
+        assert_eq!(
+            order(SortOrder::Score, 3),
+            ["Commits", "Stars", "Followers"]
+        );
+        assert_eq!(
+            order(SortOrder::Alpha, 3),
+            ["Commits", "Followers", "Stars"]
+        );
+        assert_eq!(order(SortOrder::Rank, 1), ["Stars"]);
+        assert_eq!(SortOrder::from_param(Some("bogus")), SortOrder::Rank);
+    }
 
     #[test]
-    fn abridge_score_formats_as_expected() {
-        assert_eq!(abridge_score(0), "0pt");
-        assert_eq!(abridge_score(5), "5pt");
-        assert_eq!(abridge_score(1000), "1.0kpt");
+    fn rank_filters_support_ranges_and_mixed_lists() {
+        let filter = |ranks: &[&str], min_rank: Option<&str>| {
+            let ranks = ranks
+                .iter()
+                .map(|rank| rank.to_string())
+                .collect::<Vec<_>>();
+            RankFilter::parse(&ranks, min_rank).map(|filter| {
+                [
+                    Rank::Secret,
+                    Rank::Sss,
+                    Rank::S,
+                    Rank::Aa,
+                    Rank::A,
+                    Rank::B,
+                    Rank::C,
+                    Rank::Unknown,
+                ]
+                .into_iter()
+                .filter(|rank| filter.matches(*rank))
+                .map(Rank::as_str)
+                .collect::<Vec<_>>()
+            })
+        };
+
+        assert_eq!(
+            filter(&[">=AA"], None).unwrap(),
+            ["SECRET", "SSS", "S", "AA"]
+        );
+        assert_eq!(filter(&["<A"], None).unwrap(), ["B", "C"]);
+        assert_eq!(filter(&["SS..b"], None).unwrap(), ["S", "AA", "A", "B"]);
+        assert_eq!(filter(&["S", "B", "-B"], None).unwrap(), ["S"]);
+        assert_eq!(
+            filter(&["-S"], Some("AA")).unwrap(),
+            ["SECRET", "SSS", "AA"]
+        );
+        assert_eq!(filter(&["C"], Some("A")).unwrap(), Vec::<&str>::new());
+        assert_eq!(filter(&["-C"], None).unwrap().last(), Some(&"?"));
+
+        assert!(filter(&["Z"], None).is_err());
+        assert!(filter(&[">SECRET"], None).is_err());
+        assert!(filter(&["A..?"], None).is_err());
+        assert!(filter(&[], Some("great")).is_err());
     }
 }